@@ -0,0 +1,391 @@
+use crate::api::das::{
+  GetAssetBatchParams, GetAssetParams, GetAssetProofBatchParams, GetAssetProofParams, GetAssetProofResponse,
+  GetAssetResponse, GetAssetResponseList, GetAssetsByAuthorityParams, GetAssetsByCreatorParams, GetAssetsByGroupParams,
+  GetAssetsByOwnerParams, GetTokenAccountsParams, GetTokenAccountsResponse, Req, SearchAssetsParams,
+};
+use crate::api::types::RpcErrorBody;
+use crate::error::HeliusError;
+use crate::{Helius, Result};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::warn;
+
+/// A single call of a [`DasBatch`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DasRequest {
+  GetAsset(GetAssetParams),
+  GetAssetBatch(GetAssetBatchParams),
+  GetAssetProof(GetAssetProofParams),
+  GetAssetProofBatch(GetAssetProofBatchParams),
+  GetAssetsByOwner(GetAssetsByOwnerParams),
+  GetAssetsByAuthority(GetAssetsByAuthorityParams),
+  GetAssetsByCreator(GetAssetsByCreatorParams),
+  GetAssetsByGroup(GetAssetsByGroupParams),
+  SearchAssets(SearchAssetsParams),
+  GetTokenAccounts(GetTokenAccountsParams),
+}
+
+/// Result of a [`DasRequest`], the variant matches the request
+#[derive(Clone, Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
+pub enum DasResponse {
+  /// [`DasRequest::GetAsset`]
+  Asset(Option<GetAssetResponse>),
+  /// [`DasRequest::GetAssetBatch`]
+  Assets(Vec<Option<GetAssetResponse>>),
+  /// [`DasRequest::GetAssetProof`]
+  AssetProof(Option<GetAssetProofResponse>),
+  /// [`DasRequest::GetAssetProofBatch`]
+  AssetProofs(HashMap<String, GetAssetProofResponse>),
+  /// `getAssetsBy*` and [`DasRequest::SearchAssets`]
+  AssetList(GetAssetResponseList),
+  /// [`DasRequest::GetTokenAccounts`]
+  TokenAccounts(GetTokenAccountsResponse),
+}
+
+impl DasRequest {
+  pub const fn method(&self) -> &'static str {
+    match self {
+      Self::GetAsset(_) => "getAsset",
+      Self::GetAssetBatch(_) => "getAssetBatch",
+      Self::GetAssetProof(_) => "getAssetProof",
+      Self::GetAssetProofBatch(_) => "getAssetProofBatch",
+      Self::GetAssetsByOwner(_) => "getAssetsByOwner",
+      Self::GetAssetsByAuthority(_) => "getAssetsByAuthority",
+      Self::GetAssetsByCreator(_) => "getAssetsByCreator",
+      Self::GetAssetsByGroup(_) => "getAssetsByGroup",
+      Self::SearchAssets(_) => "searchAssets",
+      Self::GetTokenAccounts(_) => "getTokenAccounts",
+    }
+  }
+
+  fn params(&self) -> serde_json::Result<Value> {
+    match self {
+      Self::GetAsset(p) => serde_json::to_value(p),
+      Self::GetAssetBatch(p) => serde_json::to_value(p),
+      Self::GetAssetProof(p) => serde_json::to_value(p),
+      Self::GetAssetProofBatch(p) => serde_json::to_value(p),
+      Self::GetAssetsByOwner(p) => serde_json::to_value(p),
+      Self::GetAssetsByAuthority(p) => serde_json::to_value(p),
+      Self::GetAssetsByCreator(p) => serde_json::to_value(p),
+      Self::GetAssetsByGroup(p) => serde_json::to_value(p),
+      Self::SearchAssets(p) => serde_json::to_value(p),
+      Self::GetTokenAccounts(p) => serde_json::to_value(p),
+    }
+  }
+
+  fn parse(&self, result: &Value) -> Result<DasResponse> {
+    fn from<T: DeserializeOwned>(result: &Value) -> Result<T> {
+      T::deserialize(result).map_err(|err| HeliusError::SerdeJson { err, text: result.to_string() })
+    }
+    Ok(match self {
+      Self::GetAsset(_) => DasResponse::Asset(from(result)?),
+      Self::GetAssetBatch(_) => DasResponse::Assets(from(result)?),
+      Self::GetAssetProof(_) => DasResponse::AssetProof(from(result)?),
+      Self::GetAssetProofBatch(_) => DasResponse::AssetProofs(from(result)?),
+      Self::GetAssetsByOwner(_)
+      | Self::GetAssetsByAuthority(_)
+      | Self::GetAssetsByCreator(_)
+      | Self::GetAssetsByGroup(_)
+      | Self::SearchAssets(_) => DasResponse::AssetList(from(result)?),
+      Self::GetTokenAccounts(_) => DasResponse::TokenAccounts(from(result)?),
+    })
+  }
+}
+
+#[derive(Deserialize)]
+struct BatchRes {
+  /// A string, a number, or `null` when the server could not read the id of the call
+  #[serde(default)]
+  id: Value,
+  result: Option<Value>,
+  error: Option<RpcErrorBody>,
+}
+
+impl BatchRes {
+  /// Index of the request this entry answers, ids are sent as strings but may come back as numbers
+  fn index(&self) -> Option<usize> {
+    match &self.id {
+      Value::String(id) => id.parse().ok(),
+      Value::Number(id) => id.as_u64().and_then(|id| usize::try_from(id).ok()),
+      _ => None,
+    }
+  }
+}
+
+/// Several DAS calls sent as one [JSON-RPC batch](https://www.jsonrpc.org/specification#batch)
+///
+/// # Example
+///```rust,no_run
+/// use selene_helius_sdk::api::das::{DasResponse, GetAssetParams, GetAssetProofParams};
+/// # async fn run(helius: selene_helius_sdk::Helius) -> selene_helius_sdk::Result<()> {
+/// let id = String::from("F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk");
+/// let results = helius
+///   .das_batch()
+///   .get_asset(GetAssetParams { id: id.clone(), display_options: None })
+///   .get_asset_proof(GetAssetProofParams { id })
+///   .send()
+///   .await?;
+/// if let Ok(DasResponse::Asset(Some(asset))) = &results[0] {
+///   println!("{}", asset.id);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct DasBatch<'a> {
+  helius: &'a Helius,
+  requests: Vec<DasRequest>,
+}
+
+impl DasBatch<'_> {
+  #[must_use]
+  pub fn push(mut self, request: DasRequest) -> Self {
+    self.requests.push(request);
+    self
+  }
+
+  #[must_use]
+  pub fn get_asset(self, params: GetAssetParams) -> Self {
+    self.push(DasRequest::GetAsset(params))
+  }
+
+  #[must_use]
+  pub fn get_asset_batch(self, params: GetAssetBatchParams) -> Self {
+    self.push(DasRequest::GetAssetBatch(params))
+  }
+
+  #[must_use]
+  pub fn get_asset_proof(self, params: GetAssetProofParams) -> Self {
+    self.push(DasRequest::GetAssetProof(params))
+  }
+
+  #[must_use]
+  pub fn get_asset_proof_batch(self, params: GetAssetProofBatchParams) -> Self {
+    self.push(DasRequest::GetAssetProofBatch(params))
+  }
+
+  #[must_use]
+  pub fn get_assets_by_owner(self, params: GetAssetsByOwnerParams) -> Self {
+    self.push(DasRequest::GetAssetsByOwner(params))
+  }
+
+  #[must_use]
+  pub fn get_assets_by_authority(self, params: GetAssetsByAuthorityParams) -> Self {
+    self.push(DasRequest::GetAssetsByAuthority(params))
+  }
+
+  #[must_use]
+  pub fn get_assets_by_creator(self, params: GetAssetsByCreatorParams) -> Self {
+    self.push(DasRequest::GetAssetsByCreator(params))
+  }
+
+  #[must_use]
+  pub fn get_assets_by_group(self, params: GetAssetsByGroupParams) -> Self {
+    self.push(DasRequest::GetAssetsByGroup(params))
+  }
+
+  #[must_use]
+  pub fn search_assets(self, params: SearchAssetsParams) -> Self {
+    self.push(DasRequest::SearchAssets(params))
+  }
+
+  #[must_use]
+  pub fn get_token_accounts(self, params: GetTokenAccountsParams) -> Self {
+    self.push(DasRequest::GetTokenAccounts(params))
+  }
+
+  pub fn len(&self) -> usize {
+    self.requests.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.requests.is_empty()
+  }
+
+  /// Send all calls in one HTTP request.
+  /// Results are in request order, a failed call only fails its own entry.
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`] if the batch itself fails
+  pub async fn send(self) -> Result<Vec<Result<DasResponse>>> {
+    self.helius.das_batch_send(&self.requests).await
+  }
+}
+
+impl Helius {
+  /// Start a [`DasBatch`]
+  pub const fn das_batch(&self) -> DasBatch<'_> {
+    DasBatch { helius: self, requests: Vec::new() }
+  }
+
+  #[tracing::instrument(skip_all, fields(calls = requests.len()))]
+  async fn das_batch_send(&self, requests: &[DasRequest]) -> Result<Vec<Result<DasResponse>>> {
+    if requests.is_empty() {
+      return Ok(Vec::new());
+    }
+    let body = requests
+      .iter()
+      .enumerate()
+      .map(|(id, r)| {
+        let params = r.params().map_err(|err| HeliusError::SerdeJson { err, text: format!("{r:?}") })?;
+        Ok(Req { id: id.to_string(), ..Req::new(r.method(), params) })
+      })
+      .collect::<Result<Vec<_>>>()?;
+    let responses: Vec<BatchRes> = self.das_handler.post(self.rpc_endpoint.clone(), &body).await?;
+    let mut by_id: HashMap<usize, BatchRes> = HashMap::new();
+    let mut unmatched = Vec::new();
+    for response in responses {
+      match response.index().filter(|i| *i < requests.len() && !by_id.contains_key(i)) {
+        Some(i) => {
+          by_id.insert(i, response);
+        },
+        None => unmatched.extend(response.error),
+      }
+    }
+    // a single error without a usable id answers the whole batch, several cannot be told apart
+    let shared = match unmatched.as_slice() {
+      [error] => Some(error.clone()),
+      [] => None,
+      errors => {
+        for error in errors {
+          warn!(code = error.code, message = %error.message, "batch error without a usable id");
+        }
+        None
+      },
+    };
+    Ok(
+      requests
+        .iter()
+        .enumerate()
+        .map(|(id, request)| match by_id.remove(&id) {
+          None => Err(shared.as_ref().map_or_else(
+            || HeliusError::MissingBatchResponse { id: id.to_string() },
+            |e| HeliusError::RpcError { code: e.code, message: e.message.clone() },
+          )),
+          Some(BatchRes { error: Some(e), .. }) => Err(HeliusError::RpcError { code: e.code, message: e.message }),
+          Some(BatchRes { result, .. }) => request.parse(&result.unwrap_or_default()),
+        })
+        .collect(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::{DasResponse, GetAssetParams, GetAssetProofParams, GetTokenAccountsParams};
+  use crate::error::HeliusError;
+  use crate::{Helius, HeliusBuilder};
+  use mockito::{Matcher, ServerGuard};
+  use serde_json::json;
+
+  async fn server() -> color_eyre::Result<(ServerGuard, Helius)> {
+    let server = mockito::Server::new_async().await;
+    let helius = HeliusBuilder::new("something").rpc_url(&server.url()).build()?;
+    Ok((server, helius))
+  }
+
+  #[tokio::test]
+  async fn batch_in_request_order() -> color_eyre::Result<()> {
+    let (mut server, helius) = server().await?;
+    let asset: serde_json::Value = serde_json::from_str(include_str!("../../../tests/fixtures/das_asset.json"))?;
+    let request = json!([
+      { "jsonrpc": "2.0", "id": "0", "method": "getAsset", "params": { "id": "a" } },
+      { "jsonrpc": "2.0", "id": "1", "method": "getAssetProof", "params": { "id": "b" } },
+      { "jsonrpc": "2.0", "id": "2", "method": "getTokenAccounts", "params": { "page": 1, "owner": "c" } },
+    ]);
+    let response = json!([
+      { "jsonrpc": "2.0", "id": "2", "result": { "total": 0, "limit": 10, "page": 1, "token_accounts": [] } },
+      { "jsonrpc": "2.0", "id": "1", "error": { "code": -32000, "message": "proof not found" } },
+      { "jsonrpc": "2.0", "id": "0", "result": asset },
+    ]);
+    let mock = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(request))
+      .with_body(response.to_string())
+      .create_async()
+      .await;
+
+    let results = helius
+      .das_batch()
+      .get_asset(GetAssetParams { id: String::from("a"), display_options: None })
+      .get_asset_proof(GetAssetProofParams { id: String::from("b") })
+      .get_token_accounts(GetTokenAccountsParams { owner: Some(String::from("c")), ..Default::default() })
+      .send()
+      .await?;
+    mock.assert_async().await;
+    assert_eq!(results.len(), 3);
+    assert!(
+      matches!(&results[0], Ok(DasResponse::Asset(Some(a))) if a.id == "F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk")
+    );
+    assert!(matches!(&results[1], Err(HeliusError::RpcError { code: -32000, .. })));
+    assert!(matches!(&results[2], Ok(DasResponse::TokenAccounts(t)) if t.limit == 10));
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn batch_missing_response() -> color_eyre::Result<()> {
+    let (mut server, helius) = server().await?;
+    server
+      .mock("POST", "/")
+      .with_body(json!([{ "jsonrpc": "2.0", "id": "0", "result": null }]).to_string())
+      .create_async()
+      .await;
+    let params = GetAssetParams { id: String::from("a"), display_options: None };
+    let results = helius.das_batch().get_asset(params.clone()).get_asset(params).send().await?;
+    assert!(matches!(&results[0], Ok(DasResponse::Asset(None))));
+    assert!(matches!(&results[1], Err(HeliusError::MissingBatchResponse { id }) if id == "1"));
+    assert!(helius.das_batch().send().await?.is_empty());
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn batch_numeric_and_null_ids() -> color_eyre::Result<()> {
+    let (mut server, helius) = server().await?;
+    server
+      .mock("POST", "/")
+      .with_body(
+        json!([
+          { "jsonrpc": "2.0", "id": 0, "result": null },
+          { "jsonrpc": "2.0", "id": null, "error": { "code": -32600, "message": "invalid request" } },
+          { "jsonrpc": "2.0", "id": null, "error": { "code": -32602, "message": "invalid params" } },
+        ])
+        .to_string(),
+      )
+      .create_async()
+      .await;
+    let params = GetAssetParams { id: String::from("a"), display_options: None };
+    let results =
+      helius.das_batch().get_asset(params.clone()).get_asset(params.clone()).get_asset(params).send().await?;
+    assert!(matches!(&results[0], Ok(DasResponse::Asset(None))));
+    assert!(matches!(&results[1], Err(HeliusError::MissingBatchResponse { id }) if id == "1"));
+    assert!(matches!(&results[2], Err(HeliusError::MissingBatchResponse { id }) if id == "2"));
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn batch_single_null_id_error() -> color_eyre::Result<()> {
+    let (mut server, helius) = server().await?;
+    server
+      .mock("POST", "/")
+      .with_body(
+        json!([
+          { "jsonrpc": "2.0", "id": 1, "result": null },
+          { "jsonrpc": "2.0", "id": null, "error": { "code": -32600, "message": "invalid request" } },
+        ])
+        .to_string(),
+      )
+      .create_async()
+      .await;
+    let params = GetAssetParams { id: String::from("a"), display_options: None };
+    let results =
+      helius.das_batch().get_asset(params.clone()).get_asset(params.clone()).get_asset(params).send().await?;
+    assert!(matches!(&results[0], Err(HeliusError::RpcError { code: -32600, .. })));
+    assert!(matches!(&results[1], Ok(DasResponse::Asset(None))));
+    assert!(matches!(&results[2], Err(HeliusError::RpcError { code: -32600, .. })));
+    Ok(())
+  }
+}
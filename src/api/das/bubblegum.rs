@@ -0,0 +1,259 @@
+use crate::api::das::{GetAssetProofResponse, GetAssetResponse};
+use crate::compression::{decode_node, parse_pubkey, Node};
+use crate::error::HeliusError;
+use crate::Result;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::{pubkey, system_program};
+
+pub const BUBBLEGUM_PROGRAM_ID: Pubkey = pubkey!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+pub const SPL_ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+pub const SPL_NOOP_PROGRAM_ID: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Anchor discriminators, the first 8 bytes of `sha256("global:<instruction>")`
+const TRANSFER: [u8; 8] = [163, 52, 200, 231, 140, 3, 69, 186];
+const BURN: [u8; 8] = [116, 110, 29, 56, 107, 219, 42, 93];
+const DELEGATE: [u8; 8] = [90, 147, 75, 178, 85, 88, 4, 137];
+
+/// Leaf of a compressed NFT with everything needed to build [Bubblegum](https://github.com/metaplex-foundation/mpl-bubblegum) instructions
+///
+/// # Example
+///```rust,no_run
+/// use selene_helius_sdk::api::das::{CompressedAsset, GetAssetParams, GetAssetProofParams};
+/// # async fn run(helius: selene_helius_sdk::Helius, new_owner: solana_sdk::pubkey::Pubkey) -> color_eyre::Result<()> {
+/// let id = String::from("JDuAFTJUHAbyxw1PQnoAg9L5LDAFmAHNSoBc1pPcNhqU");
+/// let asset = helius.get_asset(&GetAssetParams { id: id.clone(), display_options: None }).await?;
+/// let proof = helius.get_asset_proof(&GetAssetProofParams { id }).await?;
+/// if let (Some(asset), Some(proof)) = (asset, proof) {
+///   let transfer = CompressedAsset::new(&asset, &proof, 0)?.transfer(&new_owner);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompressedAsset {
+  pub merkle_tree: Pubkey,
+  pub owner: Pubkey,
+  /// Equal to `owner` when the asset is not delegated
+  pub delegate: Pubkey,
+  pub root: Node,
+  pub data_hash: Node,
+  pub creator_hash: Node,
+  pub nonce: u64,
+  pub index: u32,
+  /// Proof without the nodes stored in the canopy of the tree
+  pub proof: Vec<Pubkey>,
+}
+
+impl CompressedAsset {
+  /// `canopy_depth` is the canopy depth the tree was created with, the top `canopy_depth` nodes of the proof
+  /// are stored on chain and are not passed as accounts
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidCompressedAsset`] if `asset` is not compressed or does not match `proof`
+  pub fn new(asset: &GetAssetResponse, proof: &GetAssetProofResponse, canopy_depth: u32) -> Result<Self> {
+    let compression = asset.compression.as_ref().filter(|c| c.compressed).ok_or_else(|| {
+      HeliusError::InvalidCompressedAsset { message: format!("asset {} is not compressed", asset.id) }
+    })?;
+    if compression.tree != proof.tree_id {
+      return Err(HeliusError::InvalidCompressedAsset {
+        message: format!("asset tree {} does not match proof tree {}", compression.tree, proof.tree_id),
+      });
+    }
+    let owner = parse_pubkey(&asset.ownership.owner)?;
+    let delegate = asset.ownership.delegate.as_deref().map_or(Ok(owner), parse_pubkey)?;
+    let keep = proof.proof.len().saturating_sub(usize::try_from(canopy_depth).unwrap_or(usize::MAX));
+    Ok(Self {
+      merkle_tree: parse_pubkey(&proof.tree_id)?,
+      owner,
+      delegate,
+      root: decode_node(&proof.root)?,
+      data_hash: decode_node(&compression.data_hash)?,
+      creator_hash: decode_node(&compression.creator_hash)?,
+      nonce: u64::from(compression.leaf_id),
+      index: compression.leaf_id,
+      proof: proof.proof[..keep].iter().map(|p| parse_pubkey(p)).collect::<Result<_>>()?,
+    })
+  }
+
+  /// The tree config PDA of Bubblegum
+  pub fn tree_authority(&self) -> Pubkey {
+    Pubkey::find_program_address(&[self.merkle_tree.as_ref()], &BUBBLEGUM_PROGRAM_ID).0
+  }
+
+  /// Transfer to `new_owner`, signed by the owner
+  pub fn transfer(&self, new_owner: &Pubkey) -> Instruction {
+    self.instruction(
+      TRANSFER,
+      vec![
+        AccountMeta::new_readonly(self.tree_authority(), false),
+        AccountMeta::new_readonly(self.owner, true),
+        AccountMeta::new_readonly(self.delegate, false),
+        AccountMeta::new_readonly(*new_owner, false),
+        AccountMeta::new(self.merkle_tree, false),
+      ],
+    )
+  }
+
+  /// Burn, signed by the owner
+  pub fn burn(&self) -> Instruction {
+    self.instruction(
+      BURN,
+      vec![
+        AccountMeta::new_readonly(self.tree_authority(), false),
+        AccountMeta::new_readonly(self.owner, true),
+        AccountMeta::new_readonly(self.delegate, false),
+        AccountMeta::new(self.merkle_tree, false),
+      ],
+    )
+  }
+
+  /// Make `new_delegate` the delegate, signed by the owner
+  pub fn delegate(&self, new_delegate: &Pubkey) -> Instruction {
+    self.instruction(
+      DELEGATE,
+      vec![
+        AccountMeta::new_readonly(self.tree_authority(), false),
+        AccountMeta::new_readonly(self.owner, true),
+        AccountMeta::new_readonly(self.delegate, false),
+        AccountMeta::new_readonly(*new_delegate, false),
+        AccountMeta::new(self.merkle_tree, false),
+      ],
+    )
+  }
+
+  /// Append the program accounts and the proof to `accounts`, the arguments are shared by all three instructions
+  fn instruction(&self, discriminator: [u8; 8], mut accounts: Vec<AccountMeta>) -> Instruction {
+    accounts.extend([
+      AccountMeta::new_readonly(SPL_NOOP_PROGRAM_ID, false),
+      AccountMeta::new_readonly(SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, false),
+      AccountMeta::new_readonly(system_program::id(), false),
+    ]);
+    accounts.extend(self.proof.iter().map(|p| AccountMeta::new_readonly(*p, false)));
+    let mut data = Vec::with_capacity(8 + 32 * 3 + 8 + 4);
+    data.extend_from_slice(&discriminator);
+    data.extend_from_slice(&self.root);
+    data.extend_from_slice(&self.data_hash);
+    data.extend_from_slice(&self.creator_hash);
+    data.extend_from_slice(&self.nonce.to_le_bytes());
+    data.extend_from_slice(&self.index.to_le_bytes());
+    Instruction { program_id: BUBBLEGUM_PROGRAM_ID, accounts, data }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::bubblegum::{
+    CompressedAsset, BUBBLEGUM_PROGRAM_ID, BURN, DELEGATE, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, SPL_NOOP_PROGRAM_ID,
+    TRANSFER,
+  };
+  use crate::api::das::{Compression, GetAssetProofResponse, GetAssetResponse, Ownership};
+  use crate::error::HeliusError;
+  use solana_sdk::hash::hashv;
+  use solana_sdk::instruction::AccountMeta;
+  use solana_sdk::pubkey::Pubkey;
+  use solana_sdk::{pubkey, system_program};
+
+  const TREE: Pubkey = pubkey!("6kt1eZEr9ynbyn7pUQWuPJSkiMLmAsuG4JeBQNgGTqVt");
+  const OWNER: Pubkey = pubkey!("4zdNGgAtFsW1cQgHqkiWyRsxaAgxrSRRynnuunxzjxue");
+
+  fn encode(node: &[u8]) -> String {
+    bs58::encode(node).into_string()
+  }
+
+  fn fixture() -> (GetAssetResponse, GetAssetProofResponse) {
+    let asset = GetAssetResponse {
+      id: String::from("JDuAFTJUHAbyxw1PQnoAg9L5LDAFmAHNSoBc1pPcNhqU"),
+      compression: Some(Compression {
+        compressed: true,
+        data_hash: encode(&[1; 32]),
+        creator_hash: encode(&[2; 32]),
+        tree: TREE.to_string(),
+        leaf_id: 5,
+        ..Default::default()
+      }),
+      ownership: Ownership { owner: OWNER.to_string(), ..Default::default() },
+      ..Default::default()
+    };
+    let proof = GetAssetProofResponse {
+      root: encode(&[3; 32]),
+      proof: (10..14).map(|i| encode(&[i; 32])).collect(),
+      node_index: 21,
+      leaf: encode(&[4; 32]),
+      tree_id: TREE.to_string(),
+    };
+    (asset, proof)
+  }
+
+  #[test]
+  fn discriminators() {
+    for (name, discriminator) in [("transfer", TRANSFER), ("burn", BURN), ("delegate", DELEGATE)] {
+      assert_eq!(hashv(&[b"global:", name.as_bytes()]).to_bytes()[..8], discriminator);
+    }
+  }
+
+  #[test]
+  fn transfer_instruction() -> color_eyre::Result<()> {
+    let (asset, proof) = fixture();
+    let cnft = CompressedAsset::new(&asset, &proof, 1)?;
+    assert_eq!(cnft.delegate, OWNER);
+    assert_eq!(cnft.proof.len(), 3);
+    let new_owner = Pubkey::new_unique();
+    let ix = cnft.transfer(&new_owner);
+    assert_eq!(ix.program_id, BUBBLEGUM_PROGRAM_ID);
+    let (authority, _) = Pubkey::find_program_address(&[TREE.as_ref()], &BUBBLEGUM_PROGRAM_ID);
+    let mut accounts = vec![
+      AccountMeta::new_readonly(authority, false),
+      AccountMeta::new_readonly(OWNER, true),
+      AccountMeta::new_readonly(OWNER, false),
+      AccountMeta::new_readonly(new_owner, false),
+      AccountMeta::new(TREE, false),
+      AccountMeta::new_readonly(SPL_NOOP_PROGRAM_ID, false),
+      AccountMeta::new_readonly(SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, false),
+      AccountMeta::new_readonly(system_program::id(), false),
+    ];
+    accounts.extend((10..13).map(|i| AccountMeta::new_readonly(Pubkey::new_from_array([i; 32]), false)));
+    assert_eq!(ix.accounts, accounts);
+
+    let mut data = TRANSFER.to_vec();
+    data.extend([3; 32]);
+    data.extend([1; 32]);
+    data.extend([2; 32]);
+    data.extend(5_u64.to_le_bytes());
+    data.extend(5_u32.to_le_bytes());
+    assert_eq!(ix.data, data);
+    Ok(())
+  }
+
+  #[test]
+  fn burn_and_delegate_instructions() -> color_eyre::Result<()> {
+    let (mut asset, proof) = fixture();
+    let delegate = Pubkey::new_unique();
+    asset.ownership.delegate = Some(delegate.to_string());
+    let cnft = CompressedAsset::new(&asset, &proof, 0)?;
+
+    let burn = cnft.burn();
+    assert_eq!(burn.data[..8], BURN);
+    assert_eq!(burn.accounts.len(), 7 + 4);
+    assert_eq!(burn.accounts[2], AccountMeta::new_readonly(delegate, false));
+    assert_eq!(burn.accounts[3], AccountMeta::new(TREE, false));
+
+    let new_delegate = Pubkey::new_unique();
+    let ix = cnft.delegate(&new_delegate);
+    assert_eq!(ix.data[..8], DELEGATE);
+    assert_eq!(ix.data[8..], burn.data[8..]);
+    assert_eq!(ix.accounts[3], AccountMeta::new_readonly(new_delegate, false));
+    Ok(())
+  }
+
+  #[test]
+  fn invalid_asset() {
+    let (mut asset, mut proof) = fixture();
+    proof.tree_id = Pubkey::new_unique().to_string();
+    assert!(matches!(CompressedAsset::new(&asset, &proof, 0), Err(HeliusError::InvalidCompressedAsset { .. })));
+    let (_, proof) = fixture();
+    asset.compression = None;
+    assert!(matches!(CompressedAsset::new(&asset, &proof, 0), Err(HeliusError::InvalidCompressedAsset { .. })));
+  }
+}
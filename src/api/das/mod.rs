@@ -1,18 +1,25 @@
+mod batch;
+mod bubblegum;
 pub mod fee;
+mod stream;
 mod types;
 
 use crate::Result;
 use crate::{error, Helius};
+pub use batch::{DasBatch, DasRequest, DasResponse};
 use bincode::serialize;
+pub use bubblegum::{CompressedAsset, BUBBLEGUM_PROGRAM_ID, SPL_ACCOUNT_COMPRESSION_PROGRAM_ID, SPL_NOOP_PROGRAM_ID};
 pub use fee::{
   AllFeeLevelsRequest, FeeLevelRequest, GetPriorityFeeEstimateOptions, GetPriorityFeeEstimateRequest,
   GetPriorityFeeEstimateResponse, MicroLamportPriorityFee, MicroLamportPriorityFeeLevels, PriorityLevel,
 };
+use futures::{stream as futures_stream, StreamExt, TryStreamExt};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use solana_client::rpc_client::SerializableTransaction;
 use std::collections::HashMap;
 use std::fmt::Debug;
+pub use stream::{PageOptions, Paginated, MAX_PAGE_SIZE};
 pub use types::*;
 
 impl Helius {
@@ -23,7 +30,7 @@ impl Helius {
     T: DeserializeOwned + Default,
   {
     let request = Req::new(method, params);
-    let res: Res<T> = self.handler.post(self.rpc_endpoint.clone(), &request).await?;
+    let res: Res<T> = self.das_handler.post(self.rpc_endpoint.clone(), &request).await?;
     Ok(res.result)
   }
 
@@ -34,11 +41,20 @@ impl Helius {
     self.post("getAsset", params).await
   }
 
+  /// Ids beyond [`MAX_BATCH_SIZE`] are split into concurrent calls, see [`crate::HeliusBuilder::batch_concurrency`].
+  /// Results are in the order of `params.ids`
+  ///
   /// # Errors
   ///
   /// Will return [`crate::HeliusError`]
   pub async fn get_asset_batch(&self, params: &GetAssetBatchParams) -> Result<Vec<Option<GetAssetResponse>>> {
-    self.post("getAssetBatch", params).await
+    let chunks = params.chunks();
+    let assets: Vec<Vec<Option<GetAssetResponse>>> =
+      futures_stream::iter(chunks.iter().map(|c| self.post("getAssetBatch", c)))
+        .buffered(self.batch_concurrency)
+        .try_collect()
+        .await?;
+    Ok(assets.into_iter().flatten().collect())
   }
 
   /// # Errors
@@ -48,6 +64,8 @@ impl Helius {
     self.post("getAssetProof", params).await
   }
 
+  /// Ids beyond [`MAX_BATCH_SIZE`] are split into concurrent calls, see [`crate::HeliusBuilder::batch_concurrency`]
+  ///
   /// # Errors
   ///
   /// Will return [`crate::HeliusError`]
@@ -55,34 +73,53 @@ impl Helius {
     &self,
     params: &GetAssetProofBatchParams,
   ) -> Result<HashMap<String, GetAssetProofResponse>> {
-    self.post("getAssetProofBatch", params).await
+    let chunks = params.chunks();
+    futures_stream::iter(chunks.iter().map(|c| self.post("getAssetProofBatch", c)))
+      .buffer_unordered(self.batch_concurrency)
+      .try_fold(HashMap::new(), |mut proofs, chunk: HashMap<String, GetAssetProofResponse>| async move {
+        proofs.extend(chunk);
+        Ok(proofs)
+      })
+      .await
   }
 
   /// # Errors
   ///
   /// Will return [`crate::HeliusError`]
-  pub async fn get_assets_by_owner(&self, params: &GetAssetsByOwnerParams) -> Result<GetAssetResponseList> {
+  pub async fn get_assets_by_owner<A>(&self, params: &GetAssetsByOwnerParams<A>) -> Result<GetAssetResponseList>
+  where
+    A: Serialize + Debug + Send + Sync,
+  {
     self.post("getAssetsByOwner", params).await
   }
 
   /// # Errors
   ///
   /// Will return [`crate::HeliusError`]
-  pub async fn get_assets_by_authority(&self, params: &GetAssetsByAuthorityParams) -> Result<GetAssetResponseList> {
+  pub async fn get_assets_by_authority<A>(&self, params: &GetAssetsByAuthorityParams<A>) -> Result<GetAssetResponseList>
+  where
+    A: Serialize + Debug + Send + Sync,
+  {
     self.post("getAssetsByAuthority", params).await
   }
 
   /// # Errors
   ///
   /// Will return [`crate::HeliusError`]
-  pub async fn get_assets_by_creator(&self, params: &GetAssetsByCreatorParams) -> Result<GetAssetResponseList> {
+  pub async fn get_assets_by_creator<A>(&self, params: &GetAssetsByCreatorParams<A>) -> Result<GetAssetResponseList>
+  where
+    A: Serialize + Debug + Send + Sync,
+  {
     self.post("getAssetsByCreator", params).await
   }
 
   /// # Errors
   ///
   /// Will return [`crate::HeliusError`]
-  pub async fn get_assets_by_group(&self, params: &GetAssetsByGroupParams) -> Result<GetAssetResponseList> {
+  pub async fn get_assets_by_group<A>(&self, params: &GetAssetsByGroupParams<A>) -> Result<GetAssetResponseList>
+  where
+    A: Serialize + Debug + Send + Sync,
+  {
     self.post("getAssetsByGroup", params).await
   }
 
@@ -194,3 +231,73 @@ pub struct Res<T> {
   pub id: String,
   pub result: T,
 }
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::{GetAssetBatchParams, GetAssetProofBatchParams, GetAssetProofResponse, GetAssetResponse};
+  use crate::{Helius, HeliusBuilder};
+  use mockito::{Matcher, ServerGuard};
+  use serde_json::{json, Value};
+
+  async fn server() -> color_eyre::Result<(ServerGuard, Helius)> {
+    let server = mockito::Server::new_async().await;
+    let helius = HeliusBuilder::new("something").rpc_url(&server.url()).batch_concurrency(2).build()?;
+    Ok((server, helius))
+  }
+
+  fn request_ids(req: &mockito::Request) -> Vec<String> {
+    let body: Value = serde_json::from_slice(req.body().map(Vec::as_slice).unwrap_or_default()).unwrap_or_default();
+    serde_json::from_value(body["params"]["ids"].clone()).unwrap_or_default()
+  }
+
+  fn ids(n: usize) -> Vec<String> {
+    (0..n).map(|i| format!("asset-{i}")).collect()
+  }
+
+  #[tokio::test]
+  async fn asset_batch_chunks() -> color_eyre::Result<()> {
+    let (mut server, helius) = server().await?;
+    let mock = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({ "method": "getAssetBatch" })))
+      .with_body_from_request(|req| {
+        let ids = request_ids(req);
+        assert!(ids.len() <= 1000);
+        let assets: Vec<GetAssetResponse> =
+          ids.into_iter().map(|id| GetAssetResponse { id, ..Default::default() }).collect();
+        json!({ "jsonrpc": "2.0", "id": "1", "result": assets }).to_string().into_bytes()
+      })
+      .expect(3)
+      .create_async()
+      .await;
+    let ids = ids(2500);
+    let assets = helius.get_asset_batch(&GetAssetBatchParams { ids: ids.clone(), display_options: None }).await?;
+    mock.assert_async().await;
+    let found: Vec<String> = assets.into_iter().flatten().map(|a| a.id).collect();
+    assert_eq!(found, ids);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn asset_proof_batch_chunks() -> color_eyre::Result<()> {
+    let (mut server, helius) = server().await?;
+    let mock = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({ "method": "getAssetProofBatch" })))
+      .with_body_from_request(|req| {
+        let proofs: serde_json::Map<String, Value> = request_ids(req)
+          .into_iter()
+          .map(|id| (id.clone(), json!(GetAssetProofResponse { tree_id: id, ..Default::default() })))
+          .collect();
+        json!({ "jsonrpc": "2.0", "id": "1", "result": proofs }).to_string().into_bytes()
+      })
+      .expect(2)
+      .create_async()
+      .await;
+    let proofs = helius.get_asset_proof_batch(&GetAssetProofBatchParams { ids: ids(1001) }).await?;
+    mock.assert_async().await;
+    assert_eq!(proofs.len(), 1001);
+    assert_eq!(proofs["asset-1000"].tree_id, "asset-1000");
+    Ok(())
+  }
+}
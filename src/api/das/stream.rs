@@ -0,0 +1,322 @@
+use crate::api::das::{
+  GetAssetResponse, GetAssetResponseList, GetAssetsByAuthorityParams, GetAssetsByCreatorParams, GetAssetsByGroupParams,
+  GetAssetsByOwnerParams, Pagination, SearchAssetsParams,
+};
+use crate::error::HeliusError;
+use crate::{Helius, Result};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::fmt::Debug;
+
+/// Largest page size accepted by the DAS API
+pub const MAX_PAGE_SIZE: u32 = 1000;
+
+/// Controls how the `*_stream` methods walk through the DAS list endpoints
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageOptions {
+  /// Number of items requested per page, clamped to `1..=1000`
+  pub page_size: u32,
+  /// Stop after this many items have been yielded
+  pub max_items: Option<usize>,
+}
+
+impl Default for PageOptions {
+  fn default() -> Self {
+    Self { page_size: MAX_PAGE_SIZE, max_items: None }
+  }
+}
+
+/// Request params that carry a [`Pagination`]
+pub trait Paginated {
+  fn pagination(&self) -> &Pagination;
+  fn pagination_mut(&mut self) -> &mut Pagination;
+}
+
+macro_rules! impl_paginated {
+  ($($t:ident),*) => {
+    $(impl<A> Paginated for $t<A> {
+      fn pagination(&self) -> &Pagination {
+        &self.pagination
+      }
+
+      fn pagination_mut(&mut self) -> &mut Pagination {
+        &mut self.pagination
+      }
+    })*
+  };
+}
+
+impl_paginated!(GetAssetsByOwnerParams, GetAssetsByAuthorityParams, GetAssetsByCreatorParams, GetAssetsByGroupParams);
+
+impl Paginated for SearchAssetsParams {
+  fn pagination(&self) -> &Pagination {
+    &self.pagination
+  }
+
+  fn pagination_mut(&mut self) -> &mut Pagination {
+    &mut self.pagination
+  }
+}
+
+impl Pagination {
+  pub fn is_cursor(&self) -> bool {
+    self.before.is_some() || self.after.is_some()
+  }
+
+  /// Move to the next page.
+  /// If a `before` or `after` cursor is set, the cursor is moved to the last asset seen and `page` is cleared,
+  /// otherwise the page number is incremented.
+  pub fn advance(&mut self, last: &GetAssetResponse) {
+    if self.after.is_some() {
+      self.after = Some(last.id.clone());
+      self.page = None;
+    } else if self.before.is_some() {
+      self.before = Some(last.id.clone());
+      self.page = None;
+    } else {
+      self.page = Some(self.page.unwrap_or(1) + 1);
+    }
+  }
+}
+
+impl Helius {
+  fn paginate<P>(
+    &self,
+    method: &'static str,
+    params: &P,
+    opts: PageOptions,
+  ) -> impl Stream<Item = Result<GetAssetResponse>> + '_
+  where
+    P: Paginated + Serialize + Clone + Debug + Send + Sync + 'static,
+  {
+    let page_size = opts.page_size.clamp(1, MAX_PAGE_SIZE);
+    let mut first = params.clone();
+    let pagination = first.pagination_mut();
+    pagination.limit = Some(page_size);
+    if pagination.is_cursor() {
+      pagination.page = None;
+    }
+    stream::try_unfold(Some(first), move |state| async move {
+      let Some(mut params) = state else {
+        return Ok::<_, HeliusError>(None);
+      };
+      let page: GetAssetResponseList = self.post(method, &params).await?;
+      let next = match page.items.last() {
+        Some(last) if page.items.len() >= page_size as usize => {
+          params.pagination_mut().advance(last);
+          Some(params)
+        },
+        _ => None,
+      };
+      Ok(Some((page.items, next)))
+    })
+    .map_ok(|items| stream::iter(items.into_iter().map(Ok)))
+    .try_flatten()
+    .take(opts.max_items.unwrap_or(usize::MAX))
+  }
+
+  /// Stream every asset of [`Helius::get_assets_by_owner`], fetching pages until a short page is returned
+  pub fn get_assets_by_owner_stream<A>(
+    &self,
+    params: &GetAssetsByOwnerParams<A>,
+    opts: PageOptions,
+  ) -> impl Stream<Item = Result<GetAssetResponse>> + '_
+  where
+    A: Serialize + Clone + Debug + Send + Sync + 'static,
+  {
+    self.paginate("getAssetsByOwner", params, opts)
+  }
+
+  /// Stream every asset of [`Helius::get_assets_by_authority`], fetching pages until a short page is returned
+  pub fn get_assets_by_authority_stream<A>(
+    &self,
+    params: &GetAssetsByAuthorityParams<A>,
+    opts: PageOptions,
+  ) -> impl Stream<Item = Result<GetAssetResponse>> + '_
+  where
+    A: Serialize + Clone + Debug + Send + Sync + 'static,
+  {
+    self.paginate("getAssetsByAuthority", params, opts)
+  }
+
+  /// Stream every asset of [`Helius::get_assets_by_creator`], fetching pages until a short page is returned
+  pub fn get_assets_by_creator_stream<A>(
+    &self,
+    params: &GetAssetsByCreatorParams<A>,
+    opts: PageOptions,
+  ) -> impl Stream<Item = Result<GetAssetResponse>> + '_
+  where
+    A: Serialize + Clone + Debug + Send + Sync + 'static,
+  {
+    self.paginate("getAssetsByCreator", params, opts)
+  }
+
+  /// Stream every asset of [`Helius::get_assets_by_group`], fetching pages until a short page is returned
+  pub fn get_assets_by_group_stream<A>(
+    &self,
+    params: &GetAssetsByGroupParams<A>,
+    opts: PageOptions,
+  ) -> impl Stream<Item = Result<GetAssetResponse>> + '_
+  where
+    A: Serialize + Clone + Debug + Send + Sync + 'static,
+  {
+    self.paginate("getAssetsByGroup", params, opts)
+  }
+
+  /// Stream every asset of [`Helius::search_assets`], fetching pages until a short page is returned
+  pub fn search_assets_stream(
+    &self,
+    params: &SearchAssetsParams,
+    opts: PageOptions,
+  ) -> impl Stream<Item = Result<GetAssetResponse>> + '_ {
+    self.paginate("searchAssets", params, opts)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::{
+    GetAssetResponse, GetAssetsByCreatorParams, GetAssetsByOwnerParams, PageOptions, Pagination, SearchAssetsParams,
+  };
+  use crate::error::HeliusError;
+  use crate::{Helius, HeliusBuilder};
+  use futures::TryStreamExt;
+  use mockito::{Matcher, Server, ServerGuard};
+  use serde_json::{json, Value};
+
+  fn asset(id: usize) -> Value {
+    json!({
+      "interface": "V1_NFT",
+      "id": format!("asset-{id}"),
+      "ownership": {
+        "frozen": false,
+        "delegated": false,
+        "delegate": null,
+        "ownership_model": "single",
+        "owner": "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY"
+      },
+      "mutable": true,
+      "burnt": false
+    })
+  }
+
+  fn page(ids: std::ops::Range<usize>, limit: u32, page: u32) -> String {
+    let items: Vec<Value> = ids.map(asset).collect();
+    json!({
+      "jsonrpc": "2.0",
+      "id": "1",
+      "result": { "total": items.len(), "limit": limit, "page": page, "items": items }
+    })
+    .to_string()
+  }
+
+  async fn client() -> color_eyre::Result<(ServerGuard, Helius)> {
+    let server = Server::new_async().await;
+    let helius = HeliusBuilder::new("something").rpc_url(&server.url()).build()?;
+    Ok((server, helius))
+  }
+
+  #[tokio::test]
+  async fn stream_pages_until_short_page() -> color_eyre::Result<()> {
+    let (mut server, helius) = client().await?;
+    let mut mocks = Vec::new();
+    for (n, ids) in [(1, 0..2), (2, 2..4), (3, 4..5)] {
+      let mock = server
+        .mock("POST", "/")
+        .match_body(Matcher::PartialJson(json!({"method": "getAssetsByOwner", "params": {"page": n, "limit": 2}})))
+        .with_body(page(ids, 2, n))
+        .expect(1)
+        .create_async()
+        .await;
+      mocks.push(mock);
+    }
+    let params = GetAssetsByOwnerParams { owner_address: String::from("owner"), ..Default::default() };
+    let assets: Vec<GetAssetResponse> =
+      helius.get_assets_by_owner_stream(&params, PageOptions { page_size: 2, max_items: None }).try_collect().await?;
+    let ids: Vec<String> = assets.into_iter().map(|a| a.id).collect();
+    assert_eq!(ids, vec!["asset-0", "asset-1", "asset-2", "asset-3", "asset-4"]);
+    for m in mocks {
+      m.assert_async().await;
+    }
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn stream_stops_at_max_items() -> color_eyre::Result<()> {
+    let (mut server, helius) = client().await?;
+    let first = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({"method": "searchAssets", "params": {"page": 1}})))
+      .with_body(page(0..3, 3, 1))
+      .expect(1)
+      .create_async()
+      .await;
+    let second = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({"method": "searchAssets", "params": {"page": 2}})))
+      .with_body(page(3..6, 3, 2))
+      .expect(0)
+      .create_async()
+      .await;
+    let assets: Vec<GetAssetResponse> = helius
+      .search_assets_stream(&SearchAssetsParams::default(), PageOptions { page_size: 3, max_items: Some(2) })
+      .try_collect()
+      .await?;
+    assert_eq!(assets.len(), 2);
+    first.assert_async().await;
+    second.assert_async().await;
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn stream_walks_after_cursor() -> color_eyre::Result<()> {
+    let (mut server, helius) = client().await?;
+    let first = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({"method": "getAssetsByCreator", "params": {"after": "start"}})))
+      .with_body(page(0..2, 2, 1))
+      .create_async()
+      .await;
+    let second = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({"method": "getAssetsByCreator", "params": {"after": "asset-1"}})))
+      .with_body(page(2..3, 2, 1))
+      .create_async()
+      .await;
+    let params = GetAssetsByCreatorParams {
+      creator_address: String::from("creator"),
+      pagination: Pagination { after: Some(String::from("start")), ..Default::default() },
+      ..Default::default()
+    };
+    let assets: Vec<GetAssetResponse> =
+      helius.get_assets_by_creator_stream(&params, PageOptions { page_size: 2, max_items: None }).try_collect().await?;
+    assert_eq!(assets.len(), 3);
+    first.assert_async().await;
+    second.assert_async().await;
+    Ok(())
+  }
+
+  #[test]
+  fn cursor_pagination_drops_page() -> color_eyre::Result<()> {
+    let mut pagination = Pagination { after: Some(String::from("start")), ..Default::default() };
+    let last: GetAssetResponse = serde_json::from_value(asset(1))?;
+    pagination.advance(&last);
+    assert_eq!(serde_json::to_value(&pagination)?, json!({"limit": null, "after": "asset-1"}));
+
+    let mut pagination = Pagination::default();
+    pagination.advance(&last);
+    assert_eq!(serde_json::to_value(&pagination)?, json!({"page": 2, "limit": null}));
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn stream_yields_error() -> color_eyre::Result<()> {
+    let (mut server, helius) = client().await?;
+    let _mock = server.mock("POST", "/").with_status(400).with_body("bad").create_async().await;
+    let params = GetAssetsByOwnerParams::default();
+    let result: crate::Result<Vec<GetAssetResponse>> =
+      helius.get_assets_by_owner_stream(&params, PageOptions::default()).try_collect().await;
+    assert!(matches!(result, Err(HeliusError::BadRequest { .. })));
+    Ok(())
+  }
+}
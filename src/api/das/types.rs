@@ -1,22 +1,31 @@
 use crate::api::types::{
-  AssetSortBy, AssetSortDirection, Context, Interface, OwnershipModel, RoyaltyModel, Scope, TokenType, UseMethods,
+  AssetSortBy, AssetSortDirection, Context, Interface, OwnershipModel, RoyaltyModel, Scope, TokenStandard, TokenType,
+  UseMethods,
 };
 use crate::util::deserialize_u32_from_null;
 use bigdecimal::{BigDecimal, Zero};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// Largest number of ids accepted by `getAssetBatch` and `getAssetProofBatch`
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+/// Page based (`page` and `limit`) or cursor based (`before`/`after` and `limit`) pagination.
+/// DAS rejects requests which mix both, `page` must be `None` when a cursor is set.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct Pagination {
-  pub page: u32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub page: Option<u32>,
   pub limit: Option<u32>,
-  pub before: Option<u32>,
-  pub after: Option<u32>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub before: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub after: Option<String>,
 }
 
 impl Default for Pagination {
   fn default() -> Self {
-    Self { page: 1, limit: None, before: None, after: None }
+    Self { page: Some(1), limit: None, before: None, after: None }
   }
 }
 
@@ -57,10 +66,10 @@ pub struct SearchAssetDisplayOptions {
   pub require_full_index: bool,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct GetAssetsByGroupParams {
-  pub group_value: String,
+pub struct GetAssetsByGroupParams<A = String> {
+  pub group_value: A,
   pub group_key: String,
   #[serde(flatten)]
   pub pagination: Pagination,
@@ -68,10 +77,28 @@ pub struct GetAssetsByGroupParams {
   pub sort_by: Option<AssetSortingRequest>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+impl<A> GetAssetsByGroupParams<A> {
+  pub fn new(group_key: &str, group_value: A) -> Self {
+    Self {
+      group_value,
+      group_key: group_key.to_string(),
+      pagination: Pagination::default(),
+      display_options: None,
+      sort_by: None,
+    }
+  }
+}
+
+impl Default for GetAssetsByGroupParams {
+  fn default() -> Self {
+    Self::new("", String::new())
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct GetAssetsByCreatorParams {
-  pub creator_address: String,
+pub struct GetAssetsByCreatorParams<A = String> {
+  pub creator_address: A,
   #[serde(flatten)]
   pub pagination: Pagination,
   pub only_verified: bool,
@@ -79,26 +106,68 @@ pub struct GetAssetsByCreatorParams {
   pub sort_by: Option<AssetSortingRequest>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+impl<A> GetAssetsByCreatorParams<A> {
+  pub fn new(creator_address: A) -> Self {
+    Self {
+      creator_address,
+      pagination: Pagination::default(),
+      only_verified: false,
+      display_options: None,
+      sort_by: None,
+    }
+  }
+}
+
+impl Default for GetAssetsByCreatorParams {
+  fn default() -> Self {
+    Self::new(String::new())
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct GetAssetsByAuthorityParams {
-  pub authority_address: String,
+pub struct GetAssetsByAuthorityParams<A = String> {
+  pub authority_address: A,
   #[serde(flatten)]
   pub pagination: Pagination,
   pub display_options: Option<DisplayOptions>,
   pub sort_by: Option<AssetSortingRequest>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
+impl<A> GetAssetsByAuthorityParams<A> {
+  pub fn new(authority_address: A) -> Self {
+    Self { authority_address, pagination: Pagination::default(), display_options: None, sort_by: None }
+  }
+}
+
+impl Default for GetAssetsByAuthorityParams {
+  fn default() -> Self {
+    Self::new(String::new())
+  }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
-pub struct GetAssetsByOwnerParams {
-  pub owner_address: String,
+pub struct GetAssetsByOwnerParams<A = String> {
+  pub owner_address: A,
   #[serde(flatten)]
   pub pagination: Pagination,
   pub display_options: Option<DisplayOptions>,
   pub sort_by: Option<AssetSortingRequest>,
 }
 
+impl<A> GetAssetsByOwnerParams<A> {
+  pub fn new(owner_address: A) -> Self {
+    Self { owner_address, pagination: Pagination::default(), display_options: None, sort_by: None }
+  }
+}
+
+impl Default for GetAssetsByOwnerParams {
+  fn default() -> Self {
+    Self::new(String::new())
+  }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct AssetSortingRequest {
@@ -116,6 +185,13 @@ pub struct GetAssetProofBatchParams {
   pub ids: Vec<String>,
 }
 
+impl GetAssetProofBatchParams {
+  /// Split the ids into [`MAX_BATCH_SIZE`] sized chunks
+  pub fn chunks(&self) -> Vec<Self> {
+    self.ids.chunks(MAX_BATCH_SIZE).map(|ids| Self { ids: ids.to_vec() }).collect()
+  }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub struct GetAssetProofResponse {
   pub root: String,
@@ -139,6 +215,17 @@ pub struct GetAssetBatchParams {
   pub display_options: Option<DisplayOptions>,
 }
 
+impl GetAssetBatchParams {
+  /// Split the ids into [`MAX_BATCH_SIZE`] sized chunks
+  pub fn chunks(&self) -> Vec<Self> {
+    self
+      .ids
+      .chunks(MAX_BATCH_SIZE)
+      .map(|ids| Self { ids: ids.to_vec(), display_options: self.display_options.clone() })
+      .collect()
+  }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
 pub struct GetAssetResponse {
   pub interface: Interface,
@@ -163,6 +250,8 @@ pub struct GetAssetResponseList {
   pub grand_total: Option<bool>,
   pub total: u32,
   pub limit: u32,
+  /// `0` for cursor based requests
+  #[serde(default, deserialize_with = "deserialize_u32_from_null")]
   pub page: u32,
   pub items: Vec<GetAssetResponse>,
   /// Available when query `search assets` and `display_options.show_native_balance = true`
@@ -182,13 +271,25 @@ pub struct TokenAccountDisplayOptions {
   pub show_zero_balance: bool,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
-pub struct Ownership {
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Ownership<A = String> {
   pub frozen: bool,
   pub delegated: bool,
-  pub delegate: Option<String>,
+  pub delegate: Option<A>,
   pub ownership_model: OwnershipModel,
-  pub owner: String,
+  pub owner: A,
+}
+
+impl Default for Ownership {
+  fn default() -> Self {
+    Self {
+      frozen: false,
+      delegated: false,
+      delegate: None,
+      ownership_model: OwnershipModel::default(),
+      owner: String::new(),
+    }
+  }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
@@ -286,6 +387,7 @@ pub struct Metadata {
   pub name: String,
   #[serde(default)]
   pub symbol: String,
+  pub token_standard: Option<TokenStandard>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
@@ -339,16 +441,29 @@ pub struct TokenInfo {
   pub price_info: PriceInfo,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
-pub struct TokenAccount {
-  pub address: String,
-  pub mint: String,
-  pub owner: String,
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TokenAccount<A = String> {
+  pub address: A,
+  pub mint: A,
+  pub owner: A,
   pub amount: u64,
   pub delegated_amount: u64,
   pub frozen: bool,
 }
 
+impl Default for TokenAccount {
+  fn default() -> Self {
+    Self {
+      address: String::new(),
+      mint: String::new(),
+      owner: String::new(),
+      amount: 0,
+      delegated_amount: 0,
+      frozen: false,
+    }
+  }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
 pub struct GetTokenAccountsResponse {
   pub total: u32,
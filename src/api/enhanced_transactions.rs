@@ -1,5 +1,32 @@
-use crate::api::types::enhanced::{EnhancedTransaction, ParseTransactionsRequest};
+use crate::api::types::enhanced::{EnhancedTransaction, ParseTransactionsRequest, TransactionHistoryOptions};
+use crate::error::HeliusError;
 use crate::{Helius, Result};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+
+/// Signature to continue a filtered history search from, Helius reports it in a 404 as
+/// `Failed to find events within the search period. To continue search, query the API again with the `before` parameter set to <signature>.`
+fn history_continuation(text: &str) -> Option<String> {
+  const MARKER: &str = "parameter set to ";
+  if !text.contains("continue search") {
+    return None;
+  }
+  let start = text.find(MARKER)? + MARKER.len();
+  let signature: String = text[start..].chars().take_while(char::is_ascii_alphanumeric).collect();
+  if signature.is_empty() {
+    None
+  } else {
+    Some(signature)
+  }
+}
+
+fn history_gap(status: StatusCode, text: &str) -> Option<HeliusError> {
+  if status != StatusCode::NOT_FOUND {
+    return None;
+  }
+  history_continuation(text).map(|before| HeliusError::TransactionHistoryGap { before })
+}
 
 impl Helius {
   /// # Errors
@@ -10,13 +37,252 @@ impl Helius {
     self.handler.post(self.make_url("transactions")?, transactions).await
   }
 
+  /// Parse any number of signatures. They are split with [`ParseTransactionsRequest::from_slice`] and the chunks
+  /// are sent concurrently, see [`crate::HeliusBuilder::batch_concurrency`].
+  /// Results are in the order of `signatures`, a signature Helius did not return is a
+  /// [`HeliusError::TransactionNotParsed`]
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`] if any chunk fails
+  #[tracing::instrument(skip_all, fields(signatures = signatures.len()))]
+  pub async fn parse_transactions_all(&self, signatures: &[String]) -> Result<Vec<Result<EnhancedTransaction>>> {
+    let chunks = ParseTransactionsRequest::from_slice(signatures);
+    let parsed = stream::iter(chunks.iter().map(|c| self.parse_transaction(c)))
+      .buffer_unordered(self.batch_concurrency)
+      .try_fold(HashMap::new(), |mut parsed, txns| async move {
+        parsed.extend(txns.into_iter().map(|t| (t.signature.clone(), t)));
+        Ok(parsed)
+      })
+      .await?;
+    Ok(
+      signatures
+        .iter()
+        .map(|s| parsed.get(s).cloned().ok_or_else(|| HeliusError::TransactionNotParsed { signature: s.clone() }))
+        .collect(),
+    )
+  }
+
   /// # Errors
   ///
   /// Will return [`crate::HeliusError`]
   #[tracing::instrument(skip(self))]
   pub async fn parsed_transaction_history(&self, address: &str) -> Result<Vec<EnhancedTransaction>> {
+    self.parsed_transaction_history_with_options(address, &TransactionHistoryOptions::default()).await
+  }
+
+  /// Single page of parsed transaction history, filtered by [`TransactionHistoryOptions`]
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self))]
+  pub async fn parsed_transaction_history_with_options(
+    &self,
+    address: &str,
+    opts: &TransactionHistoryOptions,
+  ) -> Result<Vec<EnhancedTransaction>> {
     let method = format!("addresses/{address}/transactions");
-    let url = self.make_url(&method)?;
-    self.handler.get(url).await
+    let mut url = self.make_url(&method)?;
+    url.query_pairs_mut().extend_pairs(opts.query_pairs());
+    self.handler.get_with(url, history_gap).await
+  }
+
+  /// Stream the full parsed transaction history, moving `before` to the signature of the
+  /// last transaction of each page until an empty page is returned.
+  /// With a `type` or `source` filter Helius may search part of the history without a match,
+  /// the stream then continues from the signature of [`HeliusError::TransactionHistoryGap`]
+  pub fn parsed_transaction_history_stream(
+    &self,
+    address: &str,
+    opts: &TransactionHistoryOptions,
+  ) -> impl Stream<Item = Result<EnhancedTransaction>> + '_ {
+    let address = address.to_string();
+    stream::try_unfold(Some(opts.clone()), move |state| {
+      let address = address.clone();
+      async move {
+        let Some(mut opts) = state else {
+          return Ok::<_, HeliusError>(None);
+        };
+        let page = match self.parsed_transaction_history_with_options(&address, &opts).await {
+          Ok(page) => page,
+          Err(HeliusError::TransactionHistoryGap { before }) if opts.before.as_ref() != Some(&before) => {
+            opts.before = Some(before);
+            return Ok(Some((Vec::new(), Some(opts))));
+          },
+          Err(err) => return Err(err),
+        };
+        let next = page.last().map(|t| {
+          opts.before = Some(t.signature.clone());
+          opts
+        });
+        Ok(Some((page, next)))
+      }
+    })
+    .map_ok(|txns| stream::iter(txns.into_iter().map(Ok)))
+    .try_flatten()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::history_gap;
+  use crate::api::types::enhanced::{EnhancedTransaction, ParseTransactionsRequest, TransactionHistoryOptions};
+  use crate::api::types::TransactionType;
+  use crate::error::HeliusError;
+  use crate::{Helius, HeliusBuilder};
+  use color_eyre::eyre::format_err;
+  use futures::TryStreamExt;
+  use mockito::{Matcher, Server, ServerGuard};
+  use reqwest::StatusCode;
+  use serde_json::{json, Value};
+
+  fn txn(signature: &str) -> Value {
+    json!({
+      "accountData": [],
+      "description": "",
+      "type": "TRANSFER",
+      "source": "SYSTEM_PROGRAM",
+      "fee": 5000,
+      "feePayer": "M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K",
+      "signature": signature,
+      "slot": 1,
+      "nativeTransfers": [],
+      "tokenTransfers": [],
+      "transactionError": null,
+      "instructions": [],
+      "events": {},
+      "timestamp": 1
+    })
+  }
+
+  async fn client() -> color_eyre::Result<(ServerGuard, Helius)> {
+    let server = Server::new_async().await;
+    let helius = HeliusBuilder::new("something").api_url(&server.url()).build()?;
+    Ok((server, helius))
+  }
+
+  #[tokio::test]
+  async fn history_stream_follows_before() -> color_eyre::Result<()> {
+    let (mut server, helius) = client().await?;
+    let path = Matcher::Exact(String::from("/addresses/addr/transactions"));
+    let first = server
+      .mock("GET", path.clone())
+      .match_query(Matcher::Exact(String::from("api-key=something&limit=2&type=TRANSFER")))
+      .with_body(json!([txn("a"), txn("b")]).to_string())
+      .expect(1)
+      .create_async()
+      .await;
+    let second = server
+      .mock("GET", path.clone())
+      .match_query(Matcher::Exact(String::from("api-key=something&before=b&limit=2&type=TRANSFER")))
+      .with_body(json!([txn("c")]).to_string())
+      .expect(1)
+      .create_async()
+      .await;
+    let gap = server
+      .mock("GET", path.clone())
+      .match_query(Matcher::Exact(String::from("api-key=something&before=c&limit=2&type=TRANSFER")))
+      .with_status(404)
+      .with_body(r#"{"error":"Failed to find events within the search period. To continue search, query the API again with the `before` parameter set to d."}"#)
+      .expect(1)
+      .create_async()
+      .await;
+    let after_gap = server
+      .mock("GET", path.clone())
+      .match_query(Matcher::Exact(String::from("api-key=something&before=d&limit=2&type=TRANSFER")))
+      .with_body(json!([txn("e")]).to_string())
+      .expect(1)
+      .create_async()
+      .await;
+    let last = server
+      .mock("GET", path)
+      .match_query(Matcher::Exact(String::from("api-key=something&before=e&limit=2&type=TRANSFER")))
+      .with_body("[]")
+      .expect(1)
+      .create_async()
+      .await;
+
+    let opts = TransactionHistoryOptions {
+      limit: Some(2),
+      transaction_type: Some(TransactionType::Transfer),
+      ..Default::default()
+    };
+    let txns: Vec<EnhancedTransaction> = helius.parsed_transaction_history_stream("addr", &opts).try_collect().await?;
+    let sigs: Vec<String> = txns.into_iter().map(|t| t.signature).collect();
+    assert_eq!(sigs, vec!["a", "b", "c", "e"]);
+    first.assert_async().await;
+    second.assert_async().await;
+    gap.assert_async().await;
+    after_gap.assert_async().await;
+    last.assert_async().await;
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn parse_all_in_signature_order() -> color_eyre::Result<()> {
+    let (mut server, helius) = client().await?;
+    let mock = server
+      .mock("POST", "/transactions")
+      .match_query(Matcher::Any)
+      .with_body_from_request(|req| {
+        let body: ParseTransactionsRequest = serde_json::from_slice(req.body().map(Vec::as_slice).unwrap_or_default())
+          .unwrap_or(ParseTransactionsRequest { transactions: Vec::new() });
+        assert!(body.transactions.len() <= 100);
+        let txns: Vec<Value> = body.transactions.iter().rev().filter(|s| *s != "sig-42").map(|s| txn(s)).collect();
+        json!(txns).to_string().into_bytes()
+      })
+      .expect(3)
+      .create_async()
+      .await;
+    let mut sigs: Vec<String> = (0..250).map(|i| format!("sig-{i}")).collect();
+    sigs.push(String::from("sig-0"));
+    let parsed = helius.parse_transactions_all(&sigs).await?;
+    mock.assert_async().await;
+    assert_eq!(parsed.len(), 251);
+    for (sig, txn) in sigs.iter().zip(&parsed) {
+      match txn {
+        Ok(t) => assert_eq!(&t.signature, sig),
+        Err(HeliusError::TransactionNotParsed { signature }) => assert_eq!(signature, "sig-42"),
+        Err(e) => color_eyre::eyre::bail!("unexpected error {e}"),
+      }
+    }
+    assert!(parsed[42].is_err());
+    Ok(())
+  }
+
+  #[test]
+  fn history_query_pairs() {
+    let opts = TransactionHistoryOptions {
+      before: Some(String::from("sig")),
+      until: Some(String::from("until")),
+      limit: Some(10),
+      transaction_type: Some(TransactionType::NftSale),
+      source: None,
+    };
+    assert_eq!(
+      opts.query_pairs(),
+      vec![
+        ("before", String::from("sig")),
+        ("until", String::from("until")),
+        ("limit", String::from("10")),
+        ("type", String::from("NFT_SALE"))
+      ]
+    );
+    assert!(TransactionHistoryOptions::default().query_pairs().is_empty());
+  }
+
+  #[test]
+  fn history_gap_only_on_not_found() -> color_eyre::Result<()> {
+    let text = r#"{"error":"Failed to find events within the search period. To continue search, query the API again with the `before` parameter set to 5wHu1qwD7q5ifaN5nwdcDqNFo53GJqa7nLp2BeeEpcHCusb4GzARz4GjgzsEHMkBMgCJMGa6GSQ7JQdpwPW7NQn1."}"#;
+    match history_gap(StatusCode::NOT_FOUND, text) {
+      Some(HeliusError::TransactionHistoryGap { before }) => {
+        assert_eq!(before, "5wHu1qwD7q5ifaN5nwdcDqNFo53GJqa7nLp2BeeEpcHCusb4GzARz4GjgzsEHMkBMgCJMGa6GSQ7JQdpwPW7NQn1");
+      },
+      _ => return Err(format_err!("error should be TransactionHistoryGap")),
+    }
+    assert!(history_gap(StatusCode::BAD_REQUEST, text).is_none());
+    assert!(history_gap(StatusCode::NOT_FOUND, r#"{"error":"not found"}"#).is_none());
+    Ok(())
   }
 }
@@ -1,37 +1,60 @@
 pub mod das;
 pub mod enhanced_transactions;
 mod name;
+pub mod smart_transaction;
 pub mod types;
 pub mod webhook;
 
+#[cfg(feature = "metrics")]
+use crate::metrics::{cluster_label, MeteredSender, Metrics};
+use crate::middleware::{Chain, Middleware};
+use crate::rate_limit::{Quota, RateLimitedSender, RateLimits};
 use crate::request_handler::RequestHandler;
+use crate::retry::RetryPolicy;
+use crate::secret::{without_api_key, ApiKey};
+use crate::transport::{ReqwestTransport, Transport};
 use crate::Cluster;
 use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::RpcClientConfig;
 use solana_sdk::commitment_config::CommitmentConfig;
+use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
 
-const API_URL_V0: &str = "https://api-mainnet.helius-rpc.com/v0";
-const DEV_API_URL_V0: &str = "https://api-devnet.helius-rpc.com/v0";
+pub(crate) const API_URL_V0: &str = "https://api-mainnet.helius-rpc.com/v0";
+pub(crate) const DEV_API_URL_V0: &str = "https://api-devnet.helius-rpc.com/v0";
 
 static APP_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"),);
 
-fn rpc_url_from_cluster(api_key: &str, cluster: Cluster) -> String {
+fn rpc_url_from_cluster(api_key: &ApiKey, cluster: &Cluster) -> String {
   match cluster {
-    Cluster::MainnetBeta => format!("https://mainnet.helius-rpc.com/?api-key={api_key}"),
-    Cluster::Devnet => format!("https://devnet.helius-rpc.com/?api-key={api_key}"),
+    Cluster::MainnetBeta => format!("https://mainnet.helius-rpc.com/?api-key={}", api_key.expose_secret()),
+    Cluster::Devnet => format!("https://devnet.helius-rpc.com/?api-key={}", api_key.expose_secret()),
+    Cluster::Custom { rpc, .. } => rpc.clone(),
+  }
+}
+
+fn api_url_from_cluster(cluster: &Cluster) -> String {
+  match cluster {
+    Cluster::MainnetBeta => String::from(API_URL_V0),
+    Cluster::Devnet => String::from(DEV_API_URL_V0),
+    Cluster::Custom { api, .. } => api.trim_end_matches('/').to_string(),
   }
 }
 
 /// See [`HeliusBuilder`] to initialize this struct
 #[derive(Clone)]
 pub struct Helius {
-  api_key: String,
+  api_key: ApiKey,
   api_url: String,
   rpc_endpoint: Url,
   rpc: Arc<RpcClient>,
   handler: RequestHandler,
+  das_handler: RequestHandler,
+  batch_concurrency: usize,
+  #[cfg(feature = "metrics")]
+  metrics: Arc<Metrics>,
 }
 
 /// A builder to configure your [`Helius`] client
@@ -50,23 +73,37 @@ pub struct Helius {
 /// ```
 /// See also [`solana_client::nonblocking::rpc_client::RpcClient`] and [`solana_client::nonblocking::rpc_client::RpcClient::new_with_timeout_and_commitment`]
 pub struct HeliusBuilder {
-  api_key: String,
+  api_key: ApiKey,
   cluster: Cluster,
   client: Option<reqwest::Client>,
+  transport: Option<Arc<dyn Transport>>,
+  middleware: Vec<Arc<dyn Middleware>>,
+  #[cfg(feature = "metrics")]
+  metrics: Option<Arc<Metrics>>,
   commitment_config: CommitmentConfig,
   timeout: Duration,
   connect_timeout: Duration,
+  retry_policy: RetryPolicy,
+  rate_limits: RateLimits,
+  batch_concurrency: usize,
 }
 
 impl HeliusBuilder {
   pub fn new(api_key: &str) -> Self {
     Self {
-      api_key: api_key.to_string(),
+      api_key: ApiKey::new(api_key),
       cluster: Cluster::MainnetBeta,
       commitment_config: CommitmentConfig::default(),
       timeout: Duration::from_secs(10),
       connect_timeout: Duration::from_secs(5),
       client: None,
+      transport: None,
+      middleware: Vec::new(),
+      #[cfg(feature = "metrics")]
+      metrics: None,
+      retry_policy: RetryPolicy::none(),
+      rate_limits: RateLimits::default(),
+      batch_concurrency: 4,
     }
   }
 
@@ -88,6 +125,7 @@ impl HeliusBuilder {
     self
   }
 
+  /// Defaults to [`Cluster::MainnetBeta`], replaces the urls set with [`HeliusBuilder::rpc_url`] and [`HeliusBuilder::api_url`]
   #[must_use]
   pub fn cluster(mut self, cluster: Cluster) -> Self {
     self.cluster = cluster;
@@ -100,51 +138,186 @@ impl HeliusBuilder {
     self
   }
 
+  /// Send REST API and DAS requests through `transport` instead of a reqwest client, see [`crate::transport`].
+  /// Takes precedence over [`HeliusBuilder::http_client`]
+  #[must_use]
+  pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+    self.transport = Some(Arc::new(transport));
+    self
+  }
+
+  /// Run `middleware` around every REST API and DAS request, see [`crate::middleware`].
+  /// Middleware run in the order they are added
+  #[must_use]
+  pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+    self.middleware.push(Arc::new(middleware));
+    self
+  }
+
+  /// Record request metrics in `metrics` instead of a registry owned by the client, see [`crate::metrics`]
+  #[cfg(feature = "metrics")]
+  #[must_use]
+  pub fn metrics(mut self, metrics: Arc<Metrics>) -> Self {
+    self.metrics = Some(metrics);
+    self
+  }
+
+  /// Retry failed API and DAS requests, see [`RetryPolicy`]. Requests are not retried by default
+  #[must_use]
+  pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+    self.retry_policy = policy;
+    self
+  }
+
+  /// Throttle outgoing requests, see [`RateLimits`]. Requests are not throttled by default
+  #[must_use]
+  pub fn rate_limits(mut self, limits: RateLimits) -> Self {
+    self.rate_limits = limits;
+    self
+  }
+
+  /// Number of chunks of an oversized batch call, such as [`Helius::get_asset_batch`], sent at once. Defaults to 4
+  #[must_use]
+  pub fn batch_concurrency(mut self, concurrency: usize) -> Self {
+    self.batch_concurrency = concurrency.max(1);
+    self
+  }
+
+  /// Send REST API calls to this base url instead of the cluster's, e.g. `http://localhost:8080/v0`.
+  /// Shorthand for a [`Cluster::Custom`] keeping the current RPC url
+  #[must_use]
+  pub fn api_url(mut self, url: &str) -> Self {
+    let rpc = rpc_url_from_cluster(&self.api_key, &self.cluster);
+    self.cluster = Cluster::Custom { rpc, api: url.to_string() };
+    self
+  }
+
+  /// Send DAS and solana RPC calls to this url instead of the cluster's.
+  /// The url is used as is, the api key is not appended.
+  /// Shorthand for a [`Cluster::Custom`] keeping the current REST API url
+  #[must_use]
+  pub fn rpc_url(mut self, url: &str) -> Self {
+    let api = api_url_from_cluster(&self.cluster);
+    self.cluster = Cluster::Custom { rpc: url.to_string(), api };
+    self
+  }
+
   #[allow(clippy::missing_errors_doc)]
   pub fn build(self) -> crate::Result<Helius> {
-    let endpoint = rpc_url_from_cluster(&self.api_key, self.cluster);
+    let endpoint = rpc_url_from_cluster(&self.api_key, &self.cluster);
+    let rpc_endpoint = Url::parse(&endpoint)?;
+    #[cfg(feature = "metrics")]
+    let metrics = self.metrics.unwrap_or_default();
     let rpc = RpcClient::new_with_timeout_and_commitment(endpoint, self.timeout, self.commitment_config);
-    let client = match self.client {
-      None => reqwest::ClientBuilder::new()
-        .user_agent(APP_USER_AGENT)
-        .connect_timeout(self.connect_timeout)
-        .timeout(self.timeout)
-        .build()?,
-      Some(c) => c,
+    #[cfg(feature = "metrics")]
+    let rpc = RpcClient::new_sender(
+      MeteredSender::new(rpc, metrics.clone(), cluster_label(&self.cluster)),
+      RpcClientConfig::with_commitment(self.commitment_config),
+    );
+    let rpc = match self.rate_limits.rpc.map(Quota::bucket) {
+      None => rpc,
+      Some(b) => {
+        RpcClient::new_sender(RateLimitedSender::new(rpc, b), RpcClientConfig::with_commitment(self.commitment_config))
+      },
+    };
+    let transport: Arc<dyn Transport> = match (self.transport, self.client) {
+      (Some(transport), _) => transport,
+      (None, Some(client)) => Arc::new(ReqwestTransport::new(client)),
+      (None, None) => Arc::new(ReqwestTransport::new(
+        reqwest::ClientBuilder::new()
+          .user_agent(APP_USER_AGENT)
+          .connect_timeout(self.connect_timeout)
+          .timeout(self.timeout)
+          .build()?,
+      )),
     };
-    let api_url = String::from(match self.cluster {
-      Cluster::MainnetBeta => API_URL_V0,
-      Cluster::Devnet => DEV_API_URL_V0,
-    });
+    let transport: Arc<dyn Transport> =
+      if self.middleware.is_empty() { transport } else { Arc::new(Chain { transport, middleware: self.middleware }) };
+    let api_url = api_url_from_cluster(&self.cluster);
+    let handler =
+      RequestHandler::new(transport.clone(), self.retry_policy.clone(), self.rate_limits.api.map(Quota::bucket))
+        .with_api_key(self.api_key.clone());
+    let das_handler = RequestHandler::new(transport, self.retry_policy, self.rate_limits.das.map(Quota::bucket))
+      .with_api_key(self.api_key.clone());
+    #[cfg(feature = "metrics")]
+    let (handler, das_handler) = (
+      handler.with_metrics(metrics.clone(), cluster_label(&self.cluster)),
+      das_handler.with_metrics(metrics.clone(), cluster_label(&self.cluster)),
+    );
     Ok(Helius {
-      api_key: self.api_key.clone(),
+      api_key: self.api_key,
       api_url,
-      rpc_endpoint: Url::parse(&rpc_url_from_cluster(&self.api_key, self.cluster))?,
+      rpc_endpoint,
       rpc: Arc::new(rpc),
-      handler: RequestHandler::new(client),
+      handler,
+      das_handler,
+      batch_concurrency: self.batch_concurrency,
+      #[cfg(feature = "metrics")]
+      metrics,
     })
   }
 }
 
+impl Debug for HeliusBuilder {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("HeliusBuilder")
+      .field("api_key", &self.api_key)
+      .field("cluster", &self.api_key.redact(&format!("{:?}", self.cluster)))
+      .field("timeout", &self.timeout)
+      .field("connect_timeout", &self.connect_timeout)
+      .field("retry_policy", &self.retry_policy)
+      .field("batch_concurrency", &self.batch_concurrency)
+      .finish_non_exhaustive()
+  }
+}
+
+impl Debug for Helius {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("Helius")
+      .field("api_key", &self.api_key)
+      .field("api_url", &self.api_key.redact(&self.api_url))
+      .field("rpc_endpoint", &self.api_key.redact(without_api_key(&self.rpc_endpoint).as_str()))
+      .field("batch_concurrency", &self.batch_concurrency)
+      .finish_non_exhaustive()
+  }
+}
+
 impl Helius {
+  /// Metrics of the REST API and DAS requests sent by this client
+  #[cfg(feature = "metrics")]
+  pub fn metrics(&self) -> Arc<Metrics> {
+    self.metrics.clone()
+  }
+
+  /// The solana RPC client of this cluster.
+  /// Its url embeds the api key, errors it returns are only redacted once converted into a [`crate::HeliusError`]
   #[must_use]
   pub fn connection(&self) -> Arc<RpcClient> {
     self.rpc.clone()
   }
 
   fn make_url(&self, method: &str) -> crate::Result<Url> {
-    let u = format!("{}/{method}?api-key={}", self.api_url, self.api_key);
+    let u = format!("{}/{method}?api-key={}", self.api_url, self.api_key.expose_secret());
     Url::parse(&u).map_err(std::convert::Into::into)
   }
 }
 
 #[cfg(test)]
 mod tests {
+  use crate::api::das::GetAssetParams;
   use crate::api::DEV_API_URL_V0;
+  use crate::error::HeliusError;
   use crate::Cluster::Devnet;
-  use crate::HeliusBuilder;
+  use crate::{Cluster, HeliusBuilder};
   use std::time::Duration;
 
+  const KEY: &str = "d4e3b1a2-0000-secret-key";
+
+  fn assert_redacted(err: &HeliusError) {
+    assert!(!err.to_string().contains(KEY), "{err}");
+    assert!(!format!("{err:?}").contains(KEY), "{err:?}");
+  }
+
   #[test]
   fn helius_builder() {
     let helius = HeliusBuilder::new("something")
@@ -156,4 +329,81 @@ mod tests {
       .expect("failed to create client");
     assert_eq!(helius.api_url, DEV_API_URL_V0);
   }
+
+  #[test]
+  fn helius_builder_urls() -> color_eyre::Result<()> {
+    let helius =
+      HeliusBuilder::new("something").api_url("http://localhost:8080/v0/").rpc_url("http://localhost:8899").build()?;
+    assert_eq!(helius.api_url, "http://localhost:8080/v0");
+    assert_eq!(helius.rpc_endpoint.as_str(), "http://localhost:8899/");
+    assert_eq!(helius.connection().url(), "http://localhost:8899");
+    assert_eq!(helius.make_url("webhooks")?.as_str(), "http://localhost:8080/v0/webhooks?api-key=something");
+    Ok(())
+  }
+
+  #[test]
+  fn helius_builder_custom_cluster() -> color_eyre::Result<()> {
+    let cluster =
+      Cluster::Custom { rpc: String::from("https://rpc.example.com"), api: String::from("https://proxy/v0/") };
+    let helius = HeliusBuilder::new("something").cluster(cluster.clone()).build()?;
+    assert_eq!(helius.api_url, "https://proxy/v0");
+    assert_eq!(helius.connection().url(), "https://rpc.example.com");
+
+    let helius = HeliusBuilder::new("something").cluster(cluster).rpc_url("http://localhost:8899").build()?;
+    assert_eq!(helius.connection().url(), "http://localhost:8899");
+    assert_eq!(helius.api_url, "https://proxy/v0");
+
+    let helius = HeliusBuilder::new("something").cluster(Devnet).api_url("http://localhost:8080/v0").build()?;
+    assert_eq!(helius.connection().url(), "https://devnet.helius-rpc.com/?api-key=something");
+    let helius = HeliusBuilder::new("something").rpc_url("http://localhost:8899").cluster(Devnet).build()?;
+    assert_eq!(helius.api_url, DEV_API_URL_V0);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn errors_do_not_contain_api_key() -> color_eyre::Result<()> {
+    let helius = HeliusBuilder::new(KEY)
+      .api_url("http://127.0.0.1:1/v0")
+      .rpc_url(&format!("http://127.0.0.1:1/?api-key={KEY}"))
+      .build()?;
+    assert!(!format!("{helius:?}").contains(KEY));
+    assert!(!format!("{:?}", HeliusBuilder::new(KEY).rpc_url(&format!("http://127.0.0.1:1/{KEY}"))).contains(KEY));
+
+    let err = helius.get_all_webhooks().await.expect_err("connection refused");
+    assert!(matches!(err, HeliusError::ReqwestError(_)));
+    assert_redacted(&err);
+    let params =
+      GetAssetParams { id: String::from("F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk"), display_options: None };
+    assert_redacted(&helius.get_asset(&params).await.expect_err("connection refused"));
+    let err: HeliusError = helius.connection().get_slot().await.expect_err("connection refused").into();
+    assert!(matches!(err, HeliusError::SolanaClientError(_)));
+    assert_redacted(&err);
+
+    let mut server = mockito::Server::new_async().await;
+    let not_found = server
+      .mock("GET", format!("/{KEY}/v0/webhooks").as_str())
+      .match_query(mockito::Matcher::Any)
+      .with_status(404)
+      .create_async()
+      .await;
+    let unauthorized = server
+      .mock("GET", "/v0/webhooks")
+      .match_query(mockito::Matcher::Any)
+      .with_status(401)
+      .with_body(format!("invalid api key {KEY}"))
+      .create_async()
+      .await;
+    let helius = HeliusBuilder::new(KEY).api_url(&format!("{}/{KEY}/v0", server.url())).build()?;
+    let err = helius.get_all_webhooks().await.expect_err("not found");
+    assert!(matches!(err, HeliusError::NotFound { .. }));
+    assert_redacted(&err);
+    let helius = HeliusBuilder::new(KEY).api_url(&format!("{}/v0", server.url())).build()?;
+    let err = helius.get_all_webhooks().await.expect_err("unauthorized");
+    assert!(matches!(err, HeliusError::Unauthorized { .. }));
+    assert_redacted(&err);
+    not_found.assert_async().await;
+    unauthorized.assert_async().await;
+    drop(server);
+    Ok(())
+  }
 }
@@ -0,0 +1,283 @@
+use crate::api::das::PriorityLevel;
+use crate::error::HeliusError;
+use crate::{Helius, Result};
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::signers::Signers;
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::UiTransactionEncoding;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Compute units a single transaction may request
+pub const MAX_COMPUTE_UNITS: u32 = 1_400_000;
+/// Lower bound of the compute unit limit, simulations of tiny transactions can report less than a real run uses
+const MIN_COMPUTE_UNITS: u32 = 1_000;
+
+/// Options for [`Helius::send_smart_transaction`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SmartTransactionOptions {
+  /// Priority fee level to estimate, defaults to [`PriorityLevel::Medium`]
+  pub priority_level: PriorityLevel,
+  /// Upper bound for the compute unit price in micro-lamports, `None` uses the estimate as is
+  pub max_priority_fee: Option<u64>,
+  /// Percentage added to the simulated compute units
+  pub compute_unit_margin: u32,
+  /// Commitment to wait for, defaults to the commitment of [`Helius::connection`]
+  pub commitment: Option<CommitmentConfig>,
+  /// Delay between confirmation checks, the transaction is rebroadcast after every check
+  pub poll_interval: Duration,
+}
+
+impl Default for SmartTransactionOptions {
+  fn default() -> Self {
+    Self {
+      priority_level: PriorityLevel::Medium,
+      max_priority_fee: None,
+      compute_unit_margin: 10,
+      commitment: None,
+      poll_interval: Duration::from_secs(2),
+    }
+  }
+}
+
+impl SmartTransactionOptions {
+  fn compute_unit_limit(&self, units_consumed: u64) -> u32 {
+    let units = units_consumed.saturating_mul(100 + u64::from(self.compute_unit_margin)) / 100;
+    u32::try_from(units).unwrap_or(MAX_COMPUTE_UNITS).clamp(MIN_COMPUTE_UNITS, MAX_COMPUTE_UNITS)
+  }
+
+  #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+  fn compute_unit_price(&self, estimate: f64) -> u64 {
+    let price = estimate.max(0.0).ceil() as u64;
+    self.max_priority_fee.map_or(price, |max| price.min(max))
+  }
+}
+
+fn build<S: Signers + ?Sized>(
+  instructions: &[Instruction],
+  signers: &S,
+  unit_limit: u32,
+  unit_price: u64,
+  blockhash: Hash,
+) -> Result<Transaction> {
+  let payer = *signers
+    .pubkeys()
+    .first()
+    .ok_or_else(|| HeliusError::InvalidTransaction { message: String::from("no signers") })?;
+  let budget = [
+    ComputeBudgetInstruction::set_compute_unit_limit(unit_limit),
+    ComputeBudgetInstruction::set_compute_unit_price(unit_price),
+  ];
+  let instructions: Vec<Instruction> = budget.into_iter().chain(instructions.iter().cloned()).collect();
+  let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer));
+  transaction.try_sign(signers, blockhash)?;
+  Ok(transaction)
+}
+
+impl Helius {
+  /// Build, sign and send a transaction with compute budget instructions.
+  ///
+  /// The transaction is simulated to measure its compute units and priced with the
+  /// [priority fee estimate](https://docs.helius.dev/solana-rpc-nodes/alpha-priority-fee-api) for `opts.priority_level`.
+  /// It is rebroadcast until it is confirmed or its blockhash expires, RPC errors while polling are logged and retried.
+  /// The first signer pays the fees.
+  /// `instructions` must not contain compute budget instructions.
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`], [`HeliusError::TransactionFailed`] if the transaction failed on chain
+  /// and [`HeliusError::TransactionExpired`] if it was not confirmed in time
+  #[tracing::instrument(skip_all)]
+  pub async fn send_smart_transaction<S: Signers + Sync + ?Sized>(
+    &self,
+    instructions: &[Instruction],
+    signers: &S,
+    opts: &SmartTransactionOptions,
+  ) -> Result<Signature> {
+    let commitment = opts.commitment.unwrap_or_else(|| self.rpc.commitment());
+    let (blockhash, last_valid_block_height) = self.rpc.get_latest_blockhash_with_commitment(commitment).await?;
+
+    let draft = build(instructions, signers, MAX_COMPUTE_UNITS, 0, blockhash)?;
+    let estimate = self.get_estimate_priority_fee_transaction(&draft, opts.priority_level.clone()).await?;
+    let simulation = self
+      .rpc
+      .simulate_transaction_with_config(
+        &draft,
+        RpcSimulateTransactionConfig {
+          sig_verify: false,
+          replace_recent_blockhash: true,
+          commitment: Some(commitment),
+          encoding: Some(UiTransactionEncoding::Base64),
+          ..Default::default()
+        },
+      )
+      .await?
+      .value;
+    if let Some(err) = simulation.err {
+      let logs = simulation.logs.unwrap_or_default().join("\n");
+      return Err(HeliusError::InvalidTransaction { message: format!("simulation failed {err}\n{logs}") });
+    }
+    let units = simulation.units_consumed.ok_or_else(|| HeliusError::InvalidTransaction {
+      message: String::from("simulation did not report compute units"),
+    })?;
+
+    let unit_limit = opts.compute_unit_limit(units);
+    let unit_price = opts.compute_unit_price(estimate);
+    debug!(unit_limit, unit_price, "sending smart transaction");
+    let transaction = build(instructions, signers, unit_limit, unit_price, blockhash)?;
+    let config = RpcSendTransactionConfig {
+      skip_preflight: true,
+      max_retries: Some(0),
+      encoding: Some(UiTransactionEncoding::Base64),
+      ..Default::default()
+    };
+    let signature = self.rpc.send_transaction_with_config(&transaction, config).await?;
+
+    loop {
+      tokio::time::sleep(opts.poll_interval).await;
+      // RPC errors while polling are transient, only a status or the expiry of the blockhash ends the loop
+      match self.rpc.get_signature_status_with_commitment(&signature, commitment).await {
+        Ok(Some(Ok(()))) => return Ok(signature),
+        Ok(Some(Err(err))) => return Err(HeliusError::TransactionFailed { signature: signature.to_string(), err }),
+        Ok(None) => {},
+        Err(err) => warn!("status of {signature} failed {err}"),
+      }
+      match self.rpc.get_block_height_with_commitment(commitment).await {
+        Ok(height) if height > last_valid_block_height => {
+          return Err(HeliusError::TransactionExpired { signature: signature.to_string() });
+        },
+        Ok(_) => {},
+        Err(err) => warn!("block height for {signature} failed {err}"),
+      }
+      if let Err(err) = self.rpc.send_transaction_with_config(&transaction, config).await {
+        warn!("rebroadcast of {signature} failed {err}");
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::smart_transaction::{build, SmartTransactionOptions, MAX_COMPUTE_UNITS};
+  use crate::error::HeliusError;
+  use crate::{Helius, HeliusBuilder};
+  use base64::Engine;
+  use mockito::{Matcher, ServerGuard};
+  use serde_json::{json, Value};
+  use solana_sdk::compute_budget::ComputeBudgetInstruction;
+  use solana_sdk::hash::Hash;
+  use solana_sdk::signature::Keypair;
+  use solana_sdk::signer::Signer;
+  use solana_sdk::system_instruction;
+  use solana_sdk::transaction::Transaction;
+  use std::time::Duration;
+
+  #[test]
+  fn compute_budget() {
+    let opts = SmartTransactionOptions { max_priority_fee: Some(5_000), ..Default::default() };
+    assert_eq!(opts.compute_unit_limit(100_000), 110_000);
+    assert_eq!(opts.compute_unit_limit(10), 1_000);
+    assert_eq!(opts.compute_unit_limit(u64::MAX), MAX_COMPUTE_UNITS);
+    assert_eq!(opts.compute_unit_price(1_234.2), 1_235);
+    assert_eq!(opts.compute_unit_price(10_000.0), 5_000);
+    assert_eq!(opts.compute_unit_price(-1.0), 0);
+  }
+
+  #[test]
+  fn build_transaction() -> color_eyre::Result<()> {
+    let payer = Keypair::new();
+    let transfer = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1);
+    let tx = build(&[transfer], &[&payer], 200_000, 10, Hash::new_unique())?;
+    assert_eq!(tx.message.instructions.len(), 3);
+    assert_eq!(tx.message.account_keys[0], payer.pubkey());
+    assert!(tx.is_signed());
+    let none: [&Keypair; 0] = [];
+    assert!(matches!(build(&[], &none, 1, 1, Hash::default()), Err(HeliusError::InvalidTransaction { .. })));
+    Ok(())
+  }
+
+  fn rpc_result(result: &Value) -> String {
+    json!({ "jsonrpc": "2.0", "id": "1", "result": result }).to_string()
+  }
+
+  async fn mock_rpc(method: &str, server: &mut ServerGuard, result: &Value) -> mockito::Mock {
+    server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({ "method": method })))
+      .with_body(rpc_result(result))
+      .create_async()
+      .await
+  }
+
+  async fn server() -> color_eyre::Result<(ServerGuard, Helius)> {
+    let server = mockito::Server::new_async().await;
+    let helius = HeliusBuilder::new("something").rpc_url(&server.url()).build()?;
+    Ok((server, helius))
+  }
+
+  #[tokio::test]
+  async fn send_smart_transaction() -> color_eyre::Result<()> {
+    let (mut server, helius) = server().await?;
+    let context = json!({ "slot": 1 });
+    mock_rpc("getVersion", &mut server, &json!({ "solana-core": "1.18.26", "feature-set": 1 })).await;
+    let blockhash = json!({ "blockhash": Hash::new_unique().to_string(), "lastValidBlockHeight": 100 });
+    mock_rpc("getLatestBlockhash", &mut server, &json!({ "context": context, "value": blockhash })).await;
+    mock_rpc("getPriorityFeeEstimate", &mut server, &json!({ "priorityFeeEstimate": 20_000.0 })).await;
+    let simulation = json!({ "err": null, "logs": [], "accounts": null, "unitsConsumed": 1_000, "returnData": null });
+    mock_rpc("simulateTransaction", &mut server, &json!({ "context": context, "value": simulation })).await;
+    mock_rpc("getBlockHeight", &mut server, &json!(50)).await;
+    let behind = json!({ "jsonrpc": "2.0", "id": "1", "error": { "code": -32005, "message": "node is behind" } });
+    let failed = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({ "method": "getSignatureStatuses" })))
+      .with_body(behind.to_string())
+      .expect(1)
+      .create_async()
+      .await;
+    let pending = json!({ "context": context, "value": [null] });
+    let pending = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({ "method": "getSignatureStatuses" })))
+      .with_body(rpc_result(&pending))
+      .expect(1)
+      .create_async()
+      .await;
+    let status = json!({ "slot": 1, "confirmations": null, "err": null, "status": { "Ok": null }, "confirmationStatus": "finalized" });
+    mock_rpc("getSignatureStatuses", &mut server, &json!({ "context": context, "value": [status] })).await;
+    let sent = server
+      .mock("POST", "/")
+      .match_body(Matcher::PartialJson(json!({ "method": "sendTransaction" })))
+      .with_body_from_request(|req| {
+        let body: Value = serde_json::from_slice(req.body().unwrap_or(&Vec::new())).unwrap_or_default();
+        let encoded = body["params"][0].as_str().unwrap_or_default();
+        let bytes = base64::engine::general_purpose::STANDARD.decode(encoded).unwrap_or_default();
+        let tx: Transaction = bincode::deserialize(&bytes).unwrap_or_default();
+        let budget = &tx.message.instructions[..2];
+        assert_eq!(budget[0].data, ComputeBudgetInstruction::set_compute_unit_limit(1_100).data);
+        assert_eq!(budget[1].data, ComputeBudgetInstruction::set_compute_unit_price(10_000).data);
+        rpc_result(&json!(tx.signatures[0].to_string())).into_bytes()
+      })
+      .expect(3)
+      .create_async()
+      .await;
+
+    let payer = Keypair::new();
+    let transfer = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1);
+    let opts = SmartTransactionOptions {
+      max_priority_fee: Some(10_000),
+      poll_interval: Duration::from_millis(1),
+      ..Default::default()
+    };
+    let signature = helius.send_smart_transaction(&[transfer], &[&payer], &opts).await?;
+    assert_ne!(signature, solana_sdk::signature::Signature::default());
+    failed.assert_async().await;
+    pending.assert_async().await;
+    sent.assert_async().await;
+    Ok(())
+  }
+}
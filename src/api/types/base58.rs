@@ -0,0 +1,212 @@
+//! Typed addresses and signatures for the request and response types.
+//!
+//! Types such as [`crate::api::das::GetAssetsByOwnerParams`] default to `String` fields,
+//! [`Address`] and [`TxSignature`] can be used instead to validate values when they are built rather than on the server.
+//! `Default` is only implemented for the `String` variants, typed request params are built with their `new` constructor.
+//!
+//! ```rust
+//! use selene_helius_sdk::api::das::GetAssetsByOwnerParams;
+//! use selene_helius_sdk::api::types::Address;
+//!
+//! let owner: Address = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY".parse()?;
+//! let params = GetAssetsByOwnerParams::new(owner);
+//! # Ok::<(), selene_helius_sdk::error::HeliusError>(())
+//! ```
+use crate::api::das::{Ownership, TokenAccount};
+use crate::api::types::enhanced::EnhancedTransaction;
+use crate::error::HeliusError;
+use serde::de::Error as SerdeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::fmt::{Display, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// A value serialized as its base58 string
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Base58<T>(pub T);
+
+/// A [`Pubkey`] serialized as base58
+pub type Address = Base58<Pubkey>;
+
+/// A transaction [`Signature`] serialized as base58
+pub type TxSignature = Base58<Signature>;
+
+impl<T> Base58<T> {
+  pub fn into_inner(self) -> T {
+    self.0
+  }
+}
+
+impl<T> Deref for Base58<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<T> From<T> for Base58<T> {
+  fn from(value: T) -> Self {
+    Self(value)
+  }
+}
+
+impl<T: Display> Display for Base58<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    self.0.fmt(f)
+  }
+}
+
+impl<T> FromStr for Base58<T>
+where
+  T: FromStr,
+  T::Err: Display,
+{
+  type Err = HeliusError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    T::from_str(s).map(Self).map_err(|e| HeliusError::InvalidBase58 { value: s.to_string(), message: e.to_string() })
+  }
+}
+
+impl<T: Display> Serialize for Base58<T> {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&self.0)
+  }
+}
+
+impl<'de, T> Deserialize<'de> for Base58<T>
+where
+  T: FromStr,
+  T::Err: Display,
+{
+  fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    s.parse().map_err(SerdeError::custom)
+  }
+}
+
+impl Ownership {
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidBase58`] if an address is not a valid pubkey
+  pub fn typed(&self) -> Result<Ownership<Address>, HeliusError> {
+    Ok(Ownership {
+      frozen: self.frozen,
+      delegated: self.delegated,
+      delegate: self.delegate.as_deref().map(str::parse).transpose()?,
+      ownership_model: self.ownership_model.clone(),
+      owner: self.owner.parse()?,
+    })
+  }
+}
+
+impl TokenAccount {
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidBase58`] if an address is not a valid pubkey
+  pub fn typed(&self) -> Result<TokenAccount<Address>, HeliusError> {
+    Ok(TokenAccount {
+      address: self.address.parse()?,
+      mint: self.mint.parse()?,
+      owner: self.owner.parse()?,
+      amount: self.amount,
+      delegated_amount: self.delegated_amount,
+      frozen: self.frozen,
+    })
+  }
+}
+
+impl EnhancedTransaction {
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidBase58`] if the fee payer or the signature is invalid
+  pub fn typed(self) -> Result<EnhancedTransaction<Address, TxSignature>, HeliusError> {
+    Ok(EnhancedTransaction {
+      fee_payer: self.fee_payer.parse()?,
+      signature: self.signature.parse()?,
+      account_data: self.account_data,
+      description: self.description,
+      transaction_type: self.transaction_type,
+      source: self.source,
+      fee: self.fee,
+      slot: self.slot,
+      native_transfers: self.native_transfers,
+      token_transfers: self.token_transfers,
+      transaction_error: self.transaction_error,
+      instructions: self.instructions,
+      events: self.events,
+      timestamp: self.timestamp,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::{GetAssetResponse, GetAssetsByCreatorParams, GetAssetsByGroupParams, GetAssetsByOwnerParams};
+  use crate::api::types::base58::{Address, TxSignature};
+  use crate::api::types::enhanced::EnhancedTransaction;
+  use crate::api::types::webhook::WebhookData;
+  use crate::error::HeliusError;
+  use solana_sdk::pubkey::Pubkey;
+  use solana_sdk::signature::Signature;
+
+  #[test]
+  fn base58_serde() -> color_eyre::Result<()> {
+    let key = Pubkey::new_unique();
+    let address = Address::from(key);
+    assert_eq!(serde_json::to_string(&address)?, format!("\"{key}\""));
+    assert_eq!(serde_json::from_str::<Address>(&format!("\"{key}\""))?, address);
+    assert_eq!(*address, key);
+
+    let signature = TxSignature::from(Signature::from([7; 64]));
+    let json = serde_json::to_string(&signature)?;
+    assert_eq!(serde_json::from_str::<TxSignature>(&json)?, signature);
+    Ok(())
+  }
+
+  #[test]
+  fn invalid_base58() {
+    assert!(matches!("not-a-key".parse::<Address>(), Err(HeliusError::InvalidBase58 { .. })));
+    assert!(matches!("abc".parse::<TxSignature>(), Err(HeliusError::InvalidBase58 { .. })));
+    assert!(serde_json::from_str::<Address>("\"0OIl\"").is_err());
+  }
+
+  #[test]
+  fn typed_params_serialize_like_strings() -> color_eyre::Result<()> {
+    let key = Pubkey::new_unique();
+    let typed = GetAssetsByOwnerParams::new(Address::from(key));
+    let untyped = GetAssetsByOwnerParams { owner_address: key.to_string(), ..Default::default() };
+    assert_eq!(serde_json::to_value(&typed)?, serde_json::to_value(&untyped)?);
+    let typed = GetAssetsByCreatorParams { only_verified: true, ..GetAssetsByCreatorParams::new(Address::from(key)) };
+    let untyped =
+      GetAssetsByCreatorParams { creator_address: key.to_string(), only_verified: true, ..Default::default() };
+    assert_eq!(serde_json::to_value(&typed)?, serde_json::to_value(&untyped)?);
+    let typed = GetAssetsByGroupParams::new("collection", Address::from(key));
+    assert_eq!(serde_json::to_value(&typed)?["groupValue"], key.to_string());
+    let typed = WebhookData::new("https://example.com", vec![Address::from(key)]);
+    assert_eq!(serde_json::to_value(&typed)?["accountAddresses"][0], key.to_string());
+    Ok(())
+  }
+
+  #[test]
+  fn typed_responses() -> color_eyre::Result<()> {
+    let asset: GetAssetResponse = serde_json::from_str(include_str!("../../../tests/fixtures/das_asset.json"))?;
+    let ownership = asset.ownership.typed()?;
+    assert_eq!(ownership.owner.to_string(), asset.ownership.owner);
+
+    let txs: Vec<EnhancedTransaction> =
+      serde_json::from_str(include_str!("../../../tests/fixtures/webhook_enhanced.json"))?;
+    let tx = txs[0].clone();
+    let typed = tx.clone().typed()?;
+    assert_eq!(typed.signature.to_string(), tx.signature);
+    assert_eq!(serde_json::to_value(&typed)?, serde_json::to_value(&tx)?);
+
+    let mut invalid = asset.ownership;
+    invalid.owner = String::from("owner");
+    assert!(matches!(invalid.typed(), Err(HeliusError::InvalidBase58 { .. })));
+    Ok(())
+  }
+}
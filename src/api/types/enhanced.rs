@@ -3,17 +3,17 @@ use crate::util::deserialize_str_to_number;
 use serde::{Deserialize, Serialize};
 use serde_json::Number;
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct EnhancedTransaction {
+pub struct EnhancedTransaction<A = String, S = String> {
   pub account_data: Vec<AccountData>,
   pub description: String,
   #[serde(rename = "type")]
   pub transaction_type: TransactionType,
   pub source: Source,
   pub fee: i32,
-  pub fee_payer: String,
-  pub signature: String,
+  pub fee_payer: A,
+  pub signature: S,
   pub slot: i32,
   pub native_transfers: Option<Vec<NativeTransfer>>,
   pub token_transfers: Option<Vec<TokenTransfer>>,
@@ -23,7 +23,7 @@ pub struct EnhancedTransaction {
   pub timestamp: u64,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ParseTransactionsRequest {
   pub transactions: Vec<String>,
@@ -37,7 +37,45 @@ impl ParseTransactionsRequest {
   }
 }
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+/// Query options for the [parsed transaction history](https://docs.helius.dev/solana-apis/enhanced-transactions-api/parsed-transaction-history) API
+///
+/// The options are sent as query string, see [`TransactionHistoryOptions::query_pairs`]
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct TransactionHistoryOptions {
+  /// Start searching backwards from this signature
+  pub before: Option<String>,
+  /// Search until this signature is reached
+  pub until: Option<String>,
+  /// Number of transactions per page, Helius allows 1-100
+  pub limit: Option<u32>,
+  pub transaction_type: Option<TransactionType>,
+  pub source: Option<Source>,
+}
+
+impl TransactionHistoryOptions {
+  /// Query string pairs for the options that are set
+  pub fn query_pairs(&self) -> Vec<(&'static str, String)> {
+    let mut pairs = Vec::new();
+    if let Some(before) = &self.before {
+      pairs.push(("before", before.clone()));
+    }
+    if let Some(until) = &self.until {
+      pairs.push(("until", until.clone()));
+    }
+    if let Some(limit) = self.limit {
+      pairs.push(("limit", limit.to_string()));
+    }
+    if let Some(t) = &self.transaction_type {
+      pairs.push(("type", t.to_string()));
+    }
+    if let Some(source) = &self.source {
+      pairs.push(("source", source.to_string()));
+    }
+    pairs
+  }
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionEvent {
   pub nft: Option<NFTEvent>,
@@ -46,7 +84,7 @@ pub struct TransactionEvent {
   pub set_authority: Option<Vec<Authority>>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct CompressedNftEvent {
   #[serde(rename = "type")]
@@ -66,7 +104,7 @@ pub struct CompressedNftEvent {
   pub update_args: Option<serde_json::Value>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct SwapEvent {
   pub native_input: Option<NativeBalanceChange>,
@@ -78,7 +116,7 @@ pub struct SwapEvent {
   pub inner_swaps: Vec<TokenSwap>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenSwap {
   pub native_input: Option<NativeTransfer>,
@@ -90,7 +128,7 @@ pub struct TokenSwap {
   pub program_info: ProgramInfo,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ProgramInfo {
   pub source: Source,
@@ -99,7 +137,7 @@ pub struct ProgramInfo {
   pub instruction_name: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct NFTEvent {
   pub seller: String,
@@ -115,21 +153,21 @@ pub struct NFTEvent {
   pub nfts: Vec<Token>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Token {
   pub mint: String,
   pub token_standard: TokenStandard,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionError {
   #[serde(rename = "InstructionError")]
   pub instruciton_error: serde_json::Value,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct NativeBalanceChange {
   pub account: String,
@@ -137,7 +175,7 @@ pub struct NativeBalanceChange {
   pub amount: Number,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountData {
   pub account: String,
@@ -145,7 +183,7 @@ pub struct AccountData {
   pub token_balance_changes: Option<Vec<TokenBalanceChange>>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenBalanceChange {
   pub user_account: String,
@@ -154,14 +192,14 @@ pub struct TokenBalanceChange {
   pub mint: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct RawTokenAmount {
   pub token_amount: String,
   pub decimals: Number,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenTransfer {
   #[serde(flatten)]
@@ -173,14 +211,14 @@ pub struct TokenTransfer {
   pub mint: String,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct TransferUserAccounts {
   pub from_user_account: Option<String>,
   pub to_user_account: Option<String>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct NativeTransfer {
   #[serde(flatten)]
@@ -188,7 +226,7 @@ pub struct NativeTransfer {
   pub amount: Number,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Instruction {
   pub accounts: Vec<String>,
@@ -197,7 +235,7 @@ pub struct Instruction {
   pub inner_instructions: Vec<InnerInstruction>,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct InnerInstruction {
   pub accounts: Vec<String>,
@@ -205,13 +243,13 @@ pub struct InnerInstruction {
   pub program_id: String,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Collection {
   pub key: String,
   pub verified: bool,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Metadata {
   pub name: String,
@@ -228,7 +266,7 @@ pub struct Metadata {
   pub creators: Option<Vec<serde_json::Value>>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct Authority {
   pub account: String,
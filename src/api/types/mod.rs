@@ -1,9 +1,13 @@
 use serde::{Deserialize, Serialize};
 pub use serde_enum_str::{Deserialize_enum_str, Serialize_enum_str};
 
+pub mod base58;
 pub mod enhanced;
+pub mod raw;
 pub mod webhook;
 
+pub use base58::{Address, Base58, TxSignature};
+
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq, Default)]
 pub enum AssetSortBy {
   #[serde(rename = "created")]
@@ -127,6 +131,7 @@ pub struct RpcErrorBody {
   pub message: String,
 }
 
+#[deprecated(note = "use `enhanced::TransactionHistoryOptions` with `Helius::parsed_transaction_history_stream`")]
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct HeliusOptions {
   pub limit: Option<u32>,
@@ -142,7 +147,7 @@ pub enum CollectionIdentifier {
   VerifiedCollectionAddress(Vec<String>),
 }
 
-#[derive(Clone, Debug, Deserialize_enum_str, Serialize_enum_str, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize_enum_str, Serialize_enum_str, Default)]
 #[serde(rename_all = "camelCase")]
 pub enum AccountWebhookEncoding {
   #[default]
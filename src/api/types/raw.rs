@@ -0,0 +1,236 @@
+use crate::error::HeliusError;
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use solana_sdk::clock::{Slot, UnixTimestamp};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::{v0, Message, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{TransactionError, TransactionVersion, VersionedTransaction};
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+  EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, EncodedTransactionWithStatusMeta, Reward,
+  UiInnerInstructions, UiLoadedAddresses, UiMessage, UiRawMessage, UiTransaction, UiTransactionReturnData,
+  UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+use std::str::FromStr;
+
+/// Transaction of a [`crate::api::webhook::WebhookType::Raw`] webhook payload,
+/// in the `json` encoding of the `getTransaction` RPC method
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTransaction {
+  pub slot: Slot,
+  pub block_time: Option<UnixTimestamp>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub index_within_block: Option<u64>,
+  pub meta: RawTransactionMeta,
+  pub transaction: RawTransactionData,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub version: Option<TransactionVersion>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct RawTransactionData {
+  pub signatures: Vec<String>,
+  pub message: UiRawMessage,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RawTransactionMeta {
+  pub err: Option<TransactionError>,
+  pub fee: u64,
+  pub pre_balances: Vec<u64>,
+  pub post_balances: Vec<u64>,
+  /// `None` when the field is missing or `null`, as for transactions recorded before it was added
+  #[serde(default)]
+  pub inner_instructions: Option<Vec<UiInnerInstructions>>,
+  #[serde(default)]
+  pub log_messages: Option<Vec<String>>,
+  #[serde(default)]
+  pub pre_token_balances: Option<Vec<UiTransactionTokenBalance>>,
+  #[serde(default)]
+  pub post_token_balances: Option<Vec<UiTransactionTokenBalance>>,
+  #[serde(default)]
+  pub rewards: Option<Vec<Reward>>,
+  /// Accounts loaded from address lookup tables by version 0 transactions
+  #[serde(default)]
+  pub loaded_addresses: UiLoadedAddresses,
+  /// Data set by the program with `set_return_data`
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub return_data: Option<UiTransactionReturnData>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub compute_units_consumed: Option<u64>,
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T>
+where
+  T::Err: ToString,
+{
+  T::from_str(value).map_err(|e| HeliusError::InvalidBase58 { value: value.to_string(), message: e.to_string() })
+}
+
+impl RawTransactionMeta {
+  pub fn to_ui(&self) -> UiTransactionStatusMeta {
+    UiTransactionStatusMeta {
+      err: self.err.clone(),
+      status: self.err.clone().map_or(Ok(()), Err),
+      fee: self.fee,
+      pre_balances: self.pre_balances.clone(),
+      post_balances: self.post_balances.clone(),
+      inner_instructions: self.inner_instructions.clone().into(),
+      log_messages: self.log_messages.clone().into(),
+      pre_token_balances: self.pre_token_balances.clone().into(),
+      post_token_balances: self.post_token_balances.clone().into(),
+      rewards: self.rewards.clone().into(),
+      loaded_addresses: OptionSerializer::Some(self.loaded_addresses.clone()),
+      return_data: self.return_data.clone().map_or(OptionSerializer::Skip, OptionSerializer::Some),
+      compute_units_consumed: self.compute_units_consumed.map_or(OptionSerializer::Skip, OptionSerializer::Some),
+    }
+  }
+}
+
+impl RawTransaction {
+  /// First signature, identifying the transaction
+  pub fn signature(&self) -> Option<&str> {
+    self.transaction.signatures.first().map(String::as_str)
+  }
+
+  /// The transaction as returned by [`solana_client::nonblocking::rpc_client::RpcClient::get_transaction`] with the
+  /// `json` encoding, to process webhook and RPC transactions alike
+  pub fn to_confirmed(&self) -> EncodedConfirmedTransactionWithStatusMeta {
+    EncodedConfirmedTransactionWithStatusMeta {
+      slot: self.slot,
+      transaction: self.to_encoded(),
+      block_time: self.block_time,
+    }
+  }
+
+  pub fn to_encoded(&self) -> EncodedTransactionWithStatusMeta {
+    EncodedTransactionWithStatusMeta {
+      transaction: EncodedTransaction::Json(UiTransaction {
+        signatures: self.transaction.signatures.clone(),
+        message: UiMessage::Raw(self.transaction.message.clone()),
+      }),
+      meta: Some(self.meta.to_ui()),
+      version: self.version.clone(),
+    }
+  }
+
+  /// Decode the signatures and message, a message with address table lookups is a version 0 message
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidBase58`] if a signature, key, blockhash or instruction data is invalid
+  pub fn to_versioned_transaction(&self) -> Result<VersionedTransaction> {
+    let raw = &self.transaction.message;
+    let header = raw.header;
+    let account_keys = raw.account_keys.iter().map(|k| parse::<Pubkey>(k)).collect::<Result<Vec<_>>>()?;
+    let recent_blockhash = parse::<Hash>(&raw.recent_blockhash)?;
+    let instructions = raw
+      .instructions
+      .iter()
+      .map(|i| {
+        let data = bs58::decode(&i.data)
+          .into_vec()
+          .map_err(|e| HeliusError::InvalidBase58 { value: i.data.clone(), message: e.to_string() })?;
+        Ok(CompiledInstruction { program_id_index: i.program_id_index, accounts: i.accounts.clone(), data })
+      })
+      .collect::<Result<Vec<_>>>()?;
+    let lookups = raw.address_table_lookups.as_deref().unwrap_or_default();
+    let message = if lookups.is_empty() && !matches!(self.version, Some(TransactionVersion::Number(_))) {
+      VersionedMessage::Legacy(Message { header, account_keys, recent_blockhash, instructions })
+    } else {
+      let address_table_lookups = lookups
+        .iter()
+        .map(|l| {
+          Ok(v0::MessageAddressTableLookup {
+            account_key: parse(&l.account_key)?,
+            writable_indexes: l.writable_indexes.clone(),
+            readonly_indexes: l.readonly_indexes.clone(),
+          })
+        })
+        .collect::<Result<Vec<_>>>()?;
+      VersionedMessage::V0(v0::Message { header, account_keys, recent_blockhash, instructions, address_table_lookups })
+    };
+    let signatures = self.transaction.signatures.iter().map(|s| parse::<Signature>(s)).collect::<Result<Vec<_>>>()?;
+    Ok(VersionedTransaction { signatures, message })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::types::raw::RawTransaction;
+  use solana_sdk::message::VersionedMessage;
+  use solana_sdk::system_instruction::SystemInstruction;
+  use solana_transaction_status::option_serializer::OptionSerializer;
+  use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction};
+
+  const RAW: &str = include_str!("../../../tests/fixtures/webhook_raw.json");
+
+  #[test]
+  fn decode_raw_webhook() -> color_eyre::Result<()> {
+    let txns: Vec<RawTransaction> = serde_json::from_str(RAW)?;
+    let raw = &txns[0];
+    assert_eq!(raw.slot, 171_942_732);
+    assert_eq!(raw.block_time, Some(1_673_445_241));
+    assert_eq!(raw.meta.fee, 5000);
+    assert!(raw.meta.err.is_none());
+    assert!(raw.meta.loaded_addresses.writable.is_empty());
+    assert_eq!(raw.transaction.message.account_keys.len(), 3);
+    assert_eq!(
+      raw.signature(),
+      Some("5nNtjezQMYBHvgSQmoRmJPiXGsPAWmJPoGSa64xanqrauogiVzFyGQhKeFataHGXq51jR2hjbzNTkPUpP787HAmL")
+    );
+
+    let tx = raw.to_versioned_transaction()?;
+    assert!(matches!(tx.message, VersionedMessage::Legacy(_)));
+    assert_eq!(tx.signatures[0].to_string(), raw.transaction.signatures[0]);
+    assert_eq!(tx.message.static_account_keys()[1].to_string(), "NTYeYJ1wr4bpM5xo6zx5En44SvJFAd35zTxxNoERYqd");
+    let ix = &tx.message.instructions()[0];
+    assert_eq!(
+      bincode::deserialize::<SystemInstruction>(&ix.data)?,
+      SystemInstruction::Transfer { lamports: 1_000_000_000 }
+    );
+    Ok(())
+  }
+
+  #[test]
+  fn convert_to_rpc_types() -> color_eyre::Result<()> {
+    let txns: Vec<RawTransaction> = serde_json::from_str(RAW)?;
+    let confirmed = txns[0].to_confirmed();
+    assert_eq!(confirmed.slot, 171_942_732);
+    let Some(meta) = confirmed.transaction.meta.clone() else {
+      return Err(color_eyre::eyre::format_err!("missing meta"));
+    };
+    assert_eq!(meta.status, Ok(()));
+    assert!(matches!(meta.log_messages, OptionSerializer::Some(logs) if logs.len() == 2));
+    assert!(matches!(&confirmed.transaction.transaction, EncodedTransaction::Json(_)));
+
+    let json = serde_json::to_string(&confirmed)?;
+    let roundtrip: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_str(&json)?;
+    assert_eq!(roundtrip.block_time, Some(1_673_445_241));
+    Ok(())
+  }
+
+  #[test]
+  fn null_meta_fields() -> color_eyre::Result<()> {
+    let mut txns: Vec<serde_json::Value> = serde_json::from_str(RAW)?;
+    let meta = &mut txns[0]["meta"];
+    for field in ["innerInstructions", "logMessages", "preTokenBalances", "postTokenBalances", "rewards"] {
+      meta[field] = serde_json::Value::Null;
+    }
+    meta["returnData"] =
+      serde_json::json!({ "programId": "11111111111111111111111111111111", "data": ["AQ==", "base64"] });
+    let raw: RawTransaction = serde_json::from_value(txns[0].clone())?;
+    assert_eq!(raw.meta.log_messages, None);
+    assert_eq!(raw.meta.rewards, None);
+    let meta = raw.meta.to_ui();
+    assert_eq!(meta.log_messages, OptionSerializer::None);
+    assert_eq!(meta.inner_instructions, OptionSerializer::None);
+    assert!(matches!(meta.return_data, OptionSerializer::Some(data) if data.data.0 == "AQ=="));
+    Ok(())
+  }
+}
@@ -11,14 +11,14 @@ pub struct Webhook {
   pub webhook_data: WebhookData,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct WebhookData {
+pub struct WebhookData<A = String> {
   #[serde(rename = "webhookURL")]
   pub webhook_url: String,
-  #[serde(skip_serializing_if = "Vec::is_empty")]
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
   pub transaction_types: Vec<TransactionType>,
-  pub account_addresses: Vec<String>,
+  pub account_addresses: Vec<A>,
   pub webhook_type: WebhookType,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub auth_header: Option<String>,
@@ -28,6 +28,26 @@ pub struct WebhookData {
   pub encoding: AccountWebhookEncoding,
 }
 
+impl<A> WebhookData<A> {
+  pub fn new(webhook_url: &str, account_addresses: Vec<A>) -> Self {
+    Self {
+      webhook_url: webhook_url.to_string(),
+      transaction_types: Vec::new(),
+      account_addresses,
+      webhook_type: WebhookType::default(),
+      auth_header: None,
+      txn_status: TxnStatus::default(),
+      encoding: AccountWebhookEncoding::default(),
+    }
+  }
+}
+
+impl Default for WebhookData {
+  fn default() -> Self {
+    Self::new("", Vec::new())
+  }
+}
+
 #[derive(Deserialize, Serialize, Eq, PartialEq, Clone, Debug, Default)]
 pub enum WebhookType {
   #[serde(rename = "enhanced")]
@@ -45,22 +65,48 @@ pub enum WebhookType {
   DiscordDevnet,
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, Default)]
-pub struct CreateWebhookRequest {
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct CreateWebhookRequest<A = String> {
   #[serde(flatten)]
-  pub data: WebhookData,
+  pub data: WebhookData<A>,
+}
+
+impl Default for CreateWebhookRequest {
+  fn default() -> Self {
+    Self { data: WebhookData::default() }
+  }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
-pub struct CreateCollectionWebhookRequest {
+pub struct CreateCollectionWebhookRequest<A = String> {
   #[serde(flatten)]
-  pub data: WebhookData,
+  pub data: WebhookData<A>,
   pub collection_query: CollectionIdentifier,
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
-pub struct EditWebhookRequest {
+pub struct EditWebhookRequest<A = String> {
   pub webhook_id: String,
-  pub data: WebhookData,
+  pub data: WebhookData<A>,
+}
+
+/// Webhooks sharing one configuration whose addresses are split in shards of at most `shard_size` addresses,
+/// managed as one subscription, see [`crate::Helius::create_sharded_webhook`]
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ShardedWebhook {
+  pub shard_size: usize,
+  pub webhooks: Vec<Webhook>,
+}
+
+impl ShardedWebhook {
+  pub fn webhook_ids(&self) -> Vec<&str> {
+    self.webhooks.iter().map(|w| w.webhook_id.as_str()).collect()
+  }
+
+  /// Addresses of every shard
+  pub fn account_addresses(&self) -> Vec<&str> {
+    self.webhooks.iter().flat_map(|w| &w.webhook_data.account_addresses).map(String::as_str).collect()
+  }
 }
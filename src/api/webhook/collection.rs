@@ -0,0 +1,126 @@
+use crate::api::das::{GetAssetResponse, GetAssetsByCreatorParams, GetAssetsByGroupParams, PageOptions};
+use crate::api::types::CollectionIdentifier;
+use crate::api::webhook::{
+  dedup, CreateCollectionWebhookRequest, CreateWebhookRequest, ShardedWebhook, WebhookData, MAX_WEBHOOK_ADDRESSES,
+};
+use crate::{Helius, Result};
+use futures::TryStreamExt;
+
+/// Whether `creator` is the first verified creator of `asset`
+fn first_verified_creator(asset: &GetAssetResponse, creator: &str) -> bool {
+  asset.creators.iter().flatten().find(|c| c.verified).is_some_and(|c| c.address == creator)
+}
+
+impl Helius {
+  /// Mints of every asset of `collection`, walking all pages of [`Helius::get_assets_by_group`] with the
+  /// `collection` group key, or of [`Helius::get_assets_by_creator`] for verified creators, keeping the assets
+  /// whose first verified creator is one of the given creators
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self))]
+  pub async fn get_collection_mints(&self, collection: &CollectionIdentifier) -> Result<Vec<String>> {
+    let mut mints = Vec::new();
+    match collection {
+      CollectionIdentifier::VerifiedCollectionAddress(addresses) => {
+        for address in addresses {
+          let params = GetAssetsByGroupParams {
+            group_key: String::from("collection"),
+            group_value: address.clone(),
+            ..Default::default()
+          };
+          let assets: Vec<GetAssetResponse> =
+            self.get_assets_by_group_stream(&params, PageOptions::default()).try_collect().await?;
+          mints.extend(assets.into_iter().map(|a| a.id));
+        }
+      },
+      CollectionIdentifier::FirstVerifiedCreators(creators) => {
+        for creator in creators {
+          let params =
+            GetAssetsByCreatorParams { creator_address: creator.clone(), only_verified: true, ..Default::default() };
+          let assets: Vec<GetAssetResponse> =
+            self.get_assets_by_creator_stream(&params, PageOptions::default()).try_collect().await?;
+          mints.extend(assets.into_iter().filter(|a| first_verified_creator(a, creator)).map(|a| a.id));
+        }
+      },
+    }
+    Ok(dedup(&mints))
+  }
+
+  /// Create webhooks watching every mint of `request.collection_query`, see [`Helius::get_collection_mints`],
+  /// along with the addresses of `request.data`.
+  /// Collections of more than [`MAX_WEBHOOK_ADDRESSES`] mints are sharded across several webhooks
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self, request))]
+  pub async fn create_collection_webhook(&self, request: &CreateCollectionWebhookRequest) -> Result<ShardedWebhook> {
+    let mints = self.get_collection_mints(&request.collection_query).await?;
+    let account_addresses = dedup(request.data.account_addresses.iter().chain(&mints));
+    let data = WebhookData { account_addresses, ..request.data.clone() };
+    self.create_sharded_webhook(&CreateWebhookRequest { data }, MAX_WEBHOOK_ADDRESSES).await
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::GetAssetResponse;
+  use crate::api::webhook::collection::first_verified_creator;
+
+  const ASSET: &str = include_str!("../../../tests/fixtures/das_asset.json");
+
+  #[test]
+  fn first_verified() -> color_eyre::Result<()> {
+    let mut asset: GetAssetResponse = serde_json::from_str(ASSET)?;
+    assert!(first_verified_creator(&asset, "5XvhfmRjwXkGp3jHGmaKpqeerNYjkuZZBYLVQYdeVcRv"));
+    assert!(!first_verified_creator(&asset, "2RtGg6fsFiiF1EQzHqbd66AhW7R5bWeQGpTbv2UMkCdW"));
+    if let Some(creators) = asset.creators.as_mut() {
+      creators[0].verified = false;
+    }
+    assert!(first_verified_creator(&asset, "2RtGg6fsFiiF1EQzHqbd66AhW7R5bWeQGpTbv2UMkCdW"));
+    Ok(())
+  }
+
+  #[cfg(feature = "testing")]
+  #[tokio::test]
+  async fn collection_webhook() -> color_eyre::Result<()> {
+    use crate::api::types::CollectionIdentifier;
+    use crate::api::webhook::{CreateCollectionWebhookRequest, WebhookData};
+    use crate::testing::MockHelius;
+
+    let mock = MockHelius::start().await?;
+    let mut other: GetAssetResponse = serde_json::from_str(ASSET)?;
+    other.id = String::from("7bPfeS3qpQJvuKs8ZjUqLmprdtBjnGKnW4Mm2r8oNEFg");
+    if let Some(creators) = other.creators.as_mut() {
+      creators.reverse();
+    }
+    mock.add_asset(other);
+    let helius = mock.builder().build()?;
+
+    let collection = CollectionIdentifier::VerifiedCollectionAddress(vec![String::from(
+      "J1S9H3QjnRtBbbuD4HjPV6RpRhwuk4zKbxsnCHuTgh9w",
+    )]);
+    assert_eq!(
+      helius.get_collection_mints(&collection).await?,
+      ["F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk", "7bPfeS3qpQJvuKs8ZjUqLmprdtBjnGKnW4Mm2r8oNEFg"]
+    );
+
+    let request = CreateCollectionWebhookRequest {
+      data: WebhookData {
+        webhook_url: String::from("https://localhost:3000"),
+        account_addresses: vec![String::from("F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk")],
+        ..Default::default()
+      },
+      collection_query: CollectionIdentifier::FirstVerifiedCreators(vec![String::from(
+        "5XvhfmRjwXkGp3jHGmaKpqeerNYjkuZZBYLVQYdeVcRv",
+      )]),
+    };
+    let sharded = helius.create_collection_webhook(&request).await?;
+    assert_eq!(sharded.webhooks.len(), 1);
+    assert_eq!(sharded.account_addresses(), ["F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk"]);
+    assert_eq!(mock.webhooks().len(), 1);
+    Ok(())
+  }
+}
@@ -0,0 +1,316 @@
+mod collection;
+mod reconcile;
+
+pub use crate::api::types::webhook::*;
+use crate::error::HeliusError;
+use crate::{Helius, Result};
+pub use reconcile::{MatchBy, ReconcileOptions, ReconcileReport, WebhookChange};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+/// Most addresses a single webhook can watch, see [`Helius::create_sharded_webhook`] for more
+pub const MAX_WEBHOOK_ADDRESSES: usize = 100_000;
+const WEBHOOK_BASE: &str = "webhooks";
+
+fn check_address_limit(count: usize) -> Result<()> {
+  if count > MAX_WEBHOOK_ADDRESSES {
+    return Err(HeliusError::InvalidWebhook {
+      message: format!("{count} addresses, a webhook watches at most {MAX_WEBHOOK_ADDRESSES}"),
+    });
+  }
+  Ok(())
+}
+
+/// `addresses` without duplicates, in order of first occurrence
+fn dedup<'a>(addresses: impl IntoIterator<Item = &'a String>) -> Vec<String> {
+  let mut seen = HashSet::new();
+  addresses.into_iter().filter(|a| seen.insert(a.as_str())).cloned().collect()
+}
+
+/// Addresses of each shard once `addresses` replace the addresses of the `current` shards.
+/// Addresses stay in their shard, new ones fill the free room of existing shards before new shards are added.
+/// Existing shards keep their index and may end up empty, there is always at least one shard
+fn plan_shards(current: &[&[String]], addresses: &[String], size: usize) -> Vec<Vec<String>> {
+  let wanted: HashSet<&str> = addresses.iter().map(String::as_str).collect();
+  let mut placed = HashSet::new();
+  let mut plan: Vec<Vec<String>> = current
+    .iter()
+    .map(|shard| {
+      shard.iter().filter(|a| wanted.contains(a.as_str()) && placed.insert(a.as_str())).take(size).cloned().collect()
+    })
+    .collect();
+  let mut pending = addresses.iter().filter(|a| placed.insert(a.as_str())).cloned();
+  for shard in &mut plan {
+    shard.extend(pending.by_ref().take(size - shard.len()));
+  }
+  let rest: Vec<String> = pending.collect();
+  plan.extend(rest.chunks(size).map(<[String]>::to_vec));
+  if plan.is_empty() {
+    plan.push(Vec::new());
+  }
+  plan
+}
+
+impl Helius {
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self))]
+  pub async fn get_all_webhooks(&self) -> Result<Vec<Webhook>> {
+    self.handler.get(self.make_url(WEBHOOK_BASE)?).await
+  }
+
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self))]
+  pub async fn get_webhook_by_id(&self, webhook_id: &str) -> Result<Webhook> {
+    self.handler.get(self.make_url(&format!("{WEBHOOK_BASE}/{webhook_id}"))?).await
+  }
+
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self, request))]
+  pub async fn create_webhook<A>(&self, request: &CreateWebhookRequest<A>) -> Result<Webhook>
+  where
+    A: Serialize + Debug + Send + Sync,
+  {
+    check_address_limit(request.data.account_addresses.len())?;
+    self.handler.post(self.make_url(WEBHOOK_BASE)?, request).await
+  }
+
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self, request))]
+  pub async fn edit_webhook<A>(&self, request: &EditWebhookRequest<A>) -> Result<Webhook>
+  where
+    A: Serialize + Debug + Send + Sync,
+  {
+    check_address_limit(request.data.account_addresses.len())?;
+    self.handler.put(self.make_url(&format!("{WEBHOOK_BASE}/{}", request.webhook_id))?, &request.data).await
+  }
+
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self))]
+  pub async fn delete_webhook(&self, webhook_id: &str) -> Result<()> {
+    self.handler.delete(self.make_url(&format!("{WEBHOOK_BASE}/{webhook_id}"))?).await
+  }
+
+  /// Add `new_addresses` the webhook does not watch yet
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`], [`HeliusError::InvalidWebhook`] beyond [`MAX_WEBHOOK_ADDRESSES`]
+  #[tracing::instrument(skip(self, new_addresses))]
+  pub async fn append_addresses_to_webhook(&self, webhook_id: &str, new_addresses: &[String]) -> Result<Webhook> {
+    let webhook = self.get_webhook_by_id(webhook_id).await?;
+    let addresses = dedup(webhook.webhook_data.account_addresses.iter().chain(new_addresses));
+    self.put_webhook_addresses(webhook, addresses).await
+  }
+
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self, addresses))]
+  pub async fn remove_addresses_from_webhook(&self, webhook_id: &str, addresses: &[String]) -> Result<Webhook> {
+    let webhook = self.get_webhook_by_id(webhook_id).await?;
+    let removed: HashSet<&str> = addresses.iter().map(String::as_str).collect();
+    let kept = dedup(webhook.webhook_data.account_addresses.iter().filter(|a| !removed.contains(a.as_str())));
+    self.put_webhook_addresses(webhook, kept).await
+  }
+
+  /// Watch `addresses` only, duplicates are dropped
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`], [`HeliusError::InvalidWebhook`] beyond [`MAX_WEBHOOK_ADDRESSES`]
+  #[tracing::instrument(skip(self, addresses))]
+  pub async fn replace_webhook_addresses(&self, webhook_id: &str, addresses: &[String]) -> Result<Webhook> {
+    let addresses = dedup(addresses);
+    check_address_limit(addresses.len())?;
+    let webhook = self.get_webhook_by_id(webhook_id).await?;
+    self.put_webhook_addresses(webhook, addresses).await
+  }
+
+  /// Edit the addresses of `webhook`, unless they are unchanged
+  async fn put_webhook_addresses(&self, webhook: Webhook, addresses: Vec<String>) -> Result<Webhook> {
+    if webhook.webhook_data.account_addresses == addresses {
+      return Ok(webhook);
+    }
+    let data = WebhookData { account_addresses: addresses, ..webhook.webhook_data };
+    self.edit_webhook(&EditWebhookRequest { webhook_id: webhook.webhook_id, data }).await
+  }
+
+  /// Create as many webhooks as needed to watch every address of `request`, `shard_size` addresses each.
+  /// `shard_size` is capped at [`MAX_WEBHOOK_ADDRESSES`], a smaller size leaves room to add addresses later
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`], webhooks created before the failure are not deleted
+  #[tracing::instrument(skip(self, request))]
+  pub async fn create_sharded_webhook(
+    &self,
+    request: &CreateWebhookRequest,
+    shard_size: usize,
+  ) -> Result<ShardedWebhook> {
+    let sharded = ShardedWebhook { shard_size: shard_size.clamp(1, MAX_WEBHOOK_ADDRESSES), webhooks: Vec::new() };
+    self.reshard_webhook(&sharded, &request.data, &request.data.account_addresses).await
+  }
+
+  /// Fetch the webhooks of a sharded subscription, in shard order
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self))]
+  pub async fn get_sharded_webhook(&self, webhook_ids: &[String], shard_size: usize) -> Result<ShardedWebhook> {
+    let mut webhooks = Vec::with_capacity(webhook_ids.len());
+    for id in webhook_ids {
+      webhooks.push(self.get_webhook_by_id(id).await?);
+    }
+    Ok(ShardedWebhook { shard_size: shard_size.clamp(1, MAX_WEBHOOK_ADDRESSES), webhooks })
+  }
+
+  /// Watch `addresses` only across the shards of `sharded`.
+  /// Addresses stay in their webhook, shards are added with the configuration of the first webhook when needed
+  /// and deleted once empty. Only the webhooks whose addresses change are edited
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`], [`HeliusError::InvalidWebhook`] if `sharded` has no webhook
+  #[tracing::instrument(skip(self, sharded, addresses))]
+  pub async fn replace_sharded_webhook_addresses(
+    &self,
+    sharded: &ShardedWebhook,
+    addresses: &[String],
+  ) -> Result<ShardedWebhook> {
+    let template = sharded
+      .webhooks
+      .first()
+      .map(|w| w.webhook_data.clone())
+      .ok_or_else(|| HeliusError::InvalidWebhook { message: String::from("sharded webhook without any webhook") })?;
+    self.reshard_webhook(sharded, &template, addresses).await
+  }
+
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`]
+  #[tracing::instrument(skip(self, sharded))]
+  pub async fn delete_sharded_webhook(&self, sharded: &ShardedWebhook) -> Result<()> {
+    for webhook in &sharded.webhooks {
+      self.delete_webhook(&webhook.webhook_id).await?;
+    }
+    Ok(())
+  }
+
+  async fn reshard_webhook(
+    &self,
+    sharded: &ShardedWebhook,
+    template: &WebhookData,
+    addresses: &[String],
+  ) -> Result<ShardedWebhook> {
+    let current: Vec<&[String]> =
+      sharded.webhooks.iter().map(|w| w.webhook_data.account_addresses.as_slice()).collect();
+    let plan = plan_shards(&current, addresses, sharded.shard_size.clamp(1, MAX_WEBHOOK_ADDRESSES));
+    let keep_first = plan.iter().all(Vec::is_empty);
+    let mut webhooks = Vec::with_capacity(plan.len());
+    for (i, addresses) in plan.into_iter().enumerate() {
+      match sharded.webhooks.get(i) {
+        Some(webhook) if addresses.is_empty() && !(keep_first && i == 0) => {
+          self.delete_webhook(&webhook.webhook_id).await?;
+        },
+        Some(webhook) => webhooks.push(self.put_webhook_addresses(webhook.clone(), addresses).await?),
+        None => {
+          let data = WebhookData { account_addresses: addresses, ..template.clone() };
+          webhooks.push(self.create_webhook(&CreateWebhookRequest { data }).await?);
+        },
+      }
+    }
+    Ok(ShardedWebhook { shard_size: sharded.shard_size, webhooks })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::webhook::{plan_shards, MAX_WEBHOOK_ADDRESSES};
+  use crate::error::HeliusError;
+  use crate::HeliusBuilder;
+
+  fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(ToString::to_string).collect()
+  }
+
+  #[test]
+  fn shard_plans() {
+    let (first, second) = (strings(&["a", "b"]), strings(&["c"]));
+    let current = [first.as_slice(), second.as_slice()];
+    assert_eq!(
+      plan_shards(&current, &strings(&["f", "b", "c", "d", "e", "d"]), 2),
+      [strings(&["b", "f"]), strings(&["c", "d"]), strings(&["e"])]
+    );
+    assert_eq!(plan_shards(&current, &strings(&["a", "b"]), 2), [strings(&["a", "b"]), Vec::new()]);
+    assert_eq!(
+      plan_shards(&current, &strings(&["a", "b", "c"]), 1),
+      [strings(&["a"]), strings(&["c"]), strings(&["b"])]
+    );
+    assert_eq!(plan_shards(&[], &[], 2), [Vec::<String>::new()]);
+  }
+
+  #[tokio::test]
+  async fn address_limit() -> color_eyre::Result<()> {
+    let helius = HeliusBuilder::new("something").api_url("http://127.0.0.1:1/v0").build()?;
+    let addresses: Vec<String> = (0..=MAX_WEBHOOK_ADDRESSES).map(|i| i.to_string()).collect();
+    let res = helius.replace_webhook_addresses("0e8250a1-ceec-4757-ad69-cc62b0d6ed2c", &addresses).await;
+    assert!(matches!(res, Err(HeliusError::InvalidWebhook { .. })));
+    Ok(())
+  }
+
+  #[cfg(feature = "testing")]
+  #[tokio::test]
+  async fn sharded_webhook() -> color_eyre::Result<()> {
+    use crate::api::webhook::{CreateWebhookRequest, WebhookData};
+    use crate::testing::MockHelius;
+
+    let mock = MockHelius::start().await?;
+    let helius = mock.builder().build()?;
+    let data: WebhookData = WebhookData { webhook_url: String::from("https://localhost:3000"), ..Default::default() };
+    let hook = helius.create_webhook(&CreateWebhookRequest { data }).await?;
+    let hook = helius.append_addresses_to_webhook(&hook.webhook_id, &strings(&["a", "b", "a"])).await?;
+    let hook = helius.append_addresses_to_webhook(&hook.webhook_id, &strings(&["b", "c"])).await?;
+    assert_eq!(hook.webhook_data.account_addresses, ["a", "b", "c"]);
+    let hook = helius.remove_addresses_from_webhook(&hook.webhook_id, &strings(&["b", "d"])).await?;
+    assert_eq!(hook.webhook_data.account_addresses, ["a", "c"]);
+    let hook = helius.replace_webhook_addresses(&hook.webhook_id, &strings(&["d", "d", "e"])).await?;
+    assert_eq!(hook.webhook_data.account_addresses, ["d", "e"]);
+    helius.delete_webhook(&hook.webhook_id).await?;
+
+    let data = WebhookData {
+      webhook_url: String::from("https://localhost:3000"),
+      account_addresses: strings(&["a", "b", "c", "d", "e"]),
+      ..Default::default()
+    };
+    let sharded = helius.create_sharded_webhook(&CreateWebhookRequest { data }, 2).await?;
+    assert_eq!(sharded.webhooks.len(), 3);
+    assert_eq!(sharded.account_addresses(), ["a", "b", "c", "d", "e"]);
+    let ids: Vec<String> = sharded.webhook_ids().into_iter().map(String::from).collect();
+    let sharded = helius.get_sharded_webhook(&ids, 2).await?;
+    assert_eq!(sharded.account_addresses(), ["a", "b", "c", "d", "e"]);
+
+    let sharded = helius.replace_sharded_webhook_addresses(&sharded, &strings(&["a", "b", "f"])).await?;
+    assert_eq!(sharded.account_addresses(), ["a", "b", "f"]);
+    assert_eq!(sharded.webhook_ids(), &ids[..2]);
+    assert_eq!(mock.webhooks().len(), 2);
+    assert!(mock.webhooks().iter().all(|w| w.webhook_data.webhook_url == "https://localhost:3000"));
+
+    let sharded = helius.replace_sharded_webhook_addresses(&sharded, &[]).await?;
+    assert_eq!(sharded.webhook_ids(), &ids[..1]);
+    helius.delete_sharded_webhook(&sharded).await?;
+    assert!(mock.webhooks().is_empty());
+    Ok(())
+  }
+}
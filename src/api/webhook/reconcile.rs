@@ -0,0 +1,284 @@
+use crate::api::webhook::{check_address_limit, CreateWebhookRequest, EditWebhookRequest, Webhook, WebhookData};
+use crate::error::HeliusError;
+use crate::{Helius, Result};
+use std::collections::{HashMap, HashSet};
+
+/// How [`Helius::reconcile_webhooks`] pairs desired webhooks with the existing ones
+#[derive(Clone, Copy, Debug, Default)]
+pub enum MatchBy {
+  /// Same `webhook_url`, every existing webhook is managed.
+  /// Combined with [`ReconcileOptions::prune`] this deletes every webhook of the account missing from the desired list
+  #[default]
+  Url,
+  /// Same label, derived from the webhook configuration, e.g. a query parameter of its url.
+  /// Existing webhooks without a label are left alone
+  Label(fn(&WebhookData) -> Option<String>),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ReconcileOptions {
+  pub match_by: MatchBy,
+  /// Compute the changes without applying them
+  pub dry_run: bool,
+  /// Delete the managed webhooks matching no desired webhook, defaults to false
+  pub prune: bool,
+}
+
+impl Default for ReconcileOptions {
+  fn default() -> Self {
+    Self { match_by: MatchBy::Url, dry_run: false, prune: false }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub enum WebhookChange {
+  /// `webhook_id` is set once the webhook is created
+  Create {
+    webhook_id: Option<String>,
+    data: WebhookData,
+  },
+  Update {
+    webhook_id: String,
+    from: WebhookData,
+    to: WebhookData,
+  },
+  Delete {
+    webhook_id: String,
+    data: WebhookData,
+  },
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ReconcileReport {
+  /// Changes in the order they are applied: updates, creations, then deletions,
+  /// so a failure part way never deletes webhooks before their replacements exist
+  pub changes: Vec<WebhookChange>,
+  /// Ids of the matched webhooks already in the desired state
+  pub unchanged: Vec<String>,
+  pub dry_run: bool,
+}
+
+impl ReconcileReport {
+  pub fn is_noop(&self) -> bool {
+    self.changes.is_empty()
+  }
+}
+
+/// Webhooks are equal when they only differ in the order of their addresses or transaction types
+fn same_config(a: &WebhookData, b: &WebhookData) -> bool {
+  fn addresses(d: &WebhookData) -> HashSet<&str> {
+    d.account_addresses.iter().map(String::as_str).collect()
+  }
+  let same_types = a.transaction_types.iter().all(|t| b.transaction_types.contains(t))
+    && b.transaction_types.iter().all(|t| a.transaction_types.contains(t));
+  a.webhook_url == b.webhook_url
+    && a.webhook_type == b.webhook_type
+    && a.auth_header == b.auth_header
+    && a.txn_status == b.txn_status
+    && a.encoding == b.encoding
+    && same_types
+    && addresses(a) == addresses(b)
+}
+
+/// Changes turning `existing` into `desired`
+fn plan(existing: Vec<Webhook>, desired: Vec<WebhookData>, options: &ReconcileOptions) -> Result<ReconcileReport> {
+  let key = |data: &WebhookData| match options.match_by {
+    MatchBy::Url => Some(data.webhook_url.clone()),
+    MatchBy::Label(label) => label(data),
+  };
+  let mut wanted = Vec::with_capacity(desired.len());
+  let mut keys = HashSet::new();
+  for data in desired {
+    check_address_limit(data.account_addresses.len())?;
+    let Some(k) = key(&data) else {
+      return Err(HeliusError::InvalidWebhook { message: format!("no label for webhook {}", data.webhook_url) });
+    };
+    if !keys.insert(k.clone()) {
+      return Err(HeliusError::InvalidWebhook { message: format!("duplicate desired webhook {k}") });
+    }
+    wanted.push((k, data));
+  }
+
+  let mut deletes = Vec::new();
+  let mut matched = HashMap::new();
+  for webhook in existing {
+    match key(&webhook.webhook_data) {
+      Some(k) if keys.contains(&k) && !matched.contains_key(&k) => {
+        matched.insert(k, webhook);
+      },
+      Some(_) if options.prune => {
+        deletes.push(WebhookChange::Delete { webhook_id: webhook.webhook_id, data: webhook.webhook_data });
+      },
+      _ => {},
+    }
+  }
+  let mut changes = Vec::new();
+  let mut unchanged = Vec::new();
+  let mut creates = Vec::new();
+  for (k, data) in wanted {
+    match matched.remove(&k) {
+      Some(webhook) if same_config(&webhook.webhook_data, &data) => unchanged.push(webhook.webhook_id),
+      Some(webhook) => {
+        changes.push(WebhookChange::Update { webhook_id: webhook.webhook_id, from: webhook.webhook_data, to: data });
+      },
+      None => creates.push(WebhookChange::Create { webhook_id: None, data }),
+    }
+  }
+  changes.extend(creates);
+  changes.extend(deletes);
+  Ok(ReconcileReport { changes, unchanged, dry_run: options.dry_run })
+}
+
+impl Helius {
+  /// Create, update and delete webhooks until the webhooks of the account match `desired`.
+  /// With [`ReconcileOptions::dry_run`] the returned report lists the changes without applying them
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`], [`HeliusError::InvalidWebhook`] if two desired webhooks share a key.
+  /// Changes are applied one by one, those made before a failure are kept
+  #[tracing::instrument(skip_all, fields(desired = desired.len(), dry_run = options.dry_run))]
+  pub async fn reconcile_webhooks(
+    &self,
+    desired: Vec<WebhookData>,
+    options: &ReconcileOptions,
+  ) -> Result<ReconcileReport> {
+    let mut report = plan(self.get_all_webhooks().await?, desired, options)?;
+    if options.dry_run {
+      return Ok(report);
+    }
+    for change in &mut report.changes {
+      match change {
+        WebhookChange::Delete { webhook_id, .. } => self.delete_webhook(webhook_id).await?,
+        WebhookChange::Update { webhook_id, to, .. } => {
+          self.edit_webhook(&EditWebhookRequest { webhook_id: webhook_id.clone(), data: to.clone() }).await?;
+        },
+        WebhookChange::Create { webhook_id, data } => {
+          let webhook = self.create_webhook(&CreateWebhookRequest { data: data.clone() }).await?;
+          *webhook_id = Some(webhook.webhook_id);
+        },
+      }
+    }
+    Ok(report)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::types::TransactionType;
+  use crate::api::webhook::reconcile::plan;
+  use crate::api::webhook::{MatchBy, ReconcileOptions, Webhook, WebhookChange, WebhookData};
+  use crate::error::HeliusError;
+
+  fn data(url: &str, addresses: &[&str]) -> WebhookData {
+    WebhookData {
+      webhook_url: url.to_string(),
+      account_addresses: addresses.iter().map(ToString::to_string).collect(),
+      ..Default::default()
+    }
+  }
+
+  fn webhook(id: &str, webhook_data: WebhookData) -> Webhook {
+    Webhook { webhook_id: id.to_string(), wallet: String::new(), webhook_data }
+  }
+
+  fn label(data: &WebhookData) -> Option<String> {
+    url::Url::parse(&data.webhook_url).ok()?.query_pairs().find(|(k, _)| k == "label").map(|(_, v)| v.into_owned())
+  }
+
+  #[test]
+  fn plan_by_url() -> color_eyre::Result<()> {
+    let mut swaps = data("https://a", &["x", "y"]);
+    swaps.transaction_types = vec![TransactionType::Swap, TransactionType::Transfer];
+    let existing = vec![
+      webhook("1", swaps),
+      webhook("2", data("https://b", &["x"])),
+      webhook("3", data("https://c", &[])),
+      webhook("4", data("https://b", &["y"])),
+    ];
+    let mut same = data("https://a", &["y", "x"]);
+    same.transaction_types = vec![TransactionType::Transfer, TransactionType::Swap];
+    let desired = vec![same, data("https://b", &["x", "z"]), data("https://d", &["w"])];
+
+    let prune = ReconcileOptions { prune: true, ..Default::default() };
+    let report = plan(existing.clone(), desired.clone(), &prune)?;
+    assert_eq!(report.unchanged, ["1"]);
+    let summary: Vec<String> = report
+      .changes
+      .iter()
+      .map(|c| match c {
+        WebhookChange::Create { data, .. } => format!("create {}", data.webhook_url),
+        WebhookChange::Update { webhook_id, .. } => format!("update {webhook_id}"),
+        WebhookChange::Delete { webhook_id, .. } => format!("delete {webhook_id}"),
+      })
+      .collect();
+    assert_eq!(summary, ["update 2", "create https://d", "delete 3", "delete 4"]);
+
+    let report = plan(existing, desired, &ReconcileOptions::default())?;
+    assert_eq!(report.changes.len(), 2);
+
+    let duplicate =
+      plan(Vec::new(), vec![data("https://a", &[]), data("https://a", &[])], &ReconcileOptions::default());
+    assert!(matches!(duplicate, Err(HeliusError::InvalidWebhook { .. })));
+    Ok(())
+  }
+
+  #[test]
+  fn plan_by_label() -> color_eyre::Result<()> {
+    let options = ReconcileOptions { match_by: MatchBy::Label(label), prune: true, ..Default::default() };
+    let existing = vec![
+      webhook("1", data("https://a?label=orders", &["x"])),
+      webhook("2", data("https://manual", &["x"])),
+      webhook("3", data("https://b?label=stale", &["x"])),
+    ];
+    let report = plan(existing, vec![data("https://new?label=orders", &["x"])], &options)?;
+    assert!(matches!(&report.changes[..], [
+      WebhookChange::Update { webhook_id: updated, .. },
+      WebhookChange::Delete { webhook_id: deleted, .. }
+    ] if deleted == "3" && updated == "1"));
+
+    let unlabeled = plan(Vec::new(), vec![data("https://manual", &[])], &options);
+    assert!(matches!(unlabeled, Err(HeliusError::InvalidWebhook { .. })));
+    Ok(())
+  }
+
+  #[cfg(feature = "testing")]
+  #[tokio::test]
+  async fn reconcile_mock_server() -> color_eyre::Result<()> {
+    use crate::api::webhook::CreateWebhookRequest;
+    use crate::testing::MockHelius;
+
+    let mock = MockHelius::start().await?;
+    let helius = mock.builder().build()?;
+    for url in ["https://a", "https://b"] {
+      helius.create_webhook(&CreateWebhookRequest { data: data(url, &["x"]) }).await?;
+    }
+    let desired = vec![data("https://a", &["x", "y"]), data("https://c", &["z"])];
+
+    let prune = ReconcileOptions { prune: true, ..Default::default() };
+    let options = ReconcileOptions { dry_run: true, ..prune };
+    let report = helius.reconcile_webhooks(desired.clone(), &options).await?;
+    assert!(report.dry_run);
+    assert_eq!(report.changes.len(), 3);
+    assert_eq!(mock.webhooks().len(), 2);
+
+    let report = helius.reconcile_webhooks(desired.clone(), &prune).await?;
+    assert_eq!(report.changes.len(), 3);
+    assert!(report.changes.iter().all(|c| !matches!(c, WebhookChange::Create { webhook_id: None, .. })));
+    let mut webhooks: Vec<(String, Vec<String>)> =
+      mock.webhooks().into_iter().map(|w| (w.webhook_data.webhook_url, w.webhook_data.account_addresses)).collect();
+    webhooks.sort();
+    assert_eq!(
+      webhooks,
+      [
+        (String::from("https://a"), vec![String::from("x"), String::from("y")]),
+        (String::from("https://c"), vec![String::from("z")])
+      ]
+    );
+
+    let report = helius.reconcile_webhooks(desired, &prune).await?;
+    assert!(report.is_noop());
+    assert_eq!(report.unchanged.len(), 2);
+    Ok(())
+  }
+}
@@ -0,0 +1,583 @@
+//! Helpers for compressed NFTs stored in [concurrent Merkle trees](https://github.com/solana-labs/solana-program-library/tree/master/account-compression)
+use crate::api::das::{Creators, GetAssetProofResponse, GetAssetResponse, Uses};
+use crate::api::types::{self, UseMethods};
+use crate::error::HeliusError;
+use crate::Result;
+use solana_sdk::keccak::hashv;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// A leaf, node or root of a concurrent Merkle tree
+pub type Node = [u8; 32];
+
+/// Recompute the root of a tree from a leaf and its proof, the proof starts with the sibling of the leaf.
+/// This is the `recompute` of spl-account-compression.
+pub fn recompute_root(leaf: Node, proof: &[Node], leaf_index: u32) -> Node {
+  proof.iter().enumerate().fold(leaf, |node, (level, sibling)| {
+    if leaf_index.checked_shr(u32::try_from(level).unwrap_or(u32::MAX)).unwrap_or(0) & 1 == 0 {
+      hashv(&[&node, sibling]).to_bytes()
+    } else {
+      hashv(&[sibling, &node]).to_bytes()
+    }
+  })
+}
+
+/// Decode a base58 encoded [`Node`]
+///
+/// # Errors
+///
+/// Will return [`HeliusError::InvalidProof`] if `node` is not 32 bytes of base58
+pub fn decode_node(node: &str) -> Result<Node> {
+  let bytes =
+    bs58::decode(node).into_vec().map_err(|e| HeliusError::InvalidProof { message: format!("{node} {e}") })?;
+  Node::try_from(bytes.as_slice())
+    .map_err(|_| HeliusError::InvalidProof { message: format!("{node} is {} bytes, expected 32", bytes.len()) })
+}
+
+pub(crate) fn parse_pubkey(key: &str) -> Result<Pubkey> {
+  Pubkey::from_str(key).map_err(|e| HeliusError::InvalidCompressedAsset { message: format!("{key} {e}") })
+}
+
+fn int<T: TryFrom<V>, V: Copy + std::fmt::Display>(value: V, field: &str) -> Result<T> {
+  T::try_from(value)
+    .map_err(|_| HeliusError::InvalidCompressedAsset { message: format!("{field} {value} out of range") })
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenStandard {
+  NonFungible,
+  FungibleAsset,
+  Fungible,
+  NonFungibleEdition,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeafCollection {
+  pub key: Pubkey,
+  pub verified: bool,
+}
+
+/// The `MetadataArgs` a compressed NFT was minted with, `data_hash` is computed from its borsh encoding
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetadataArgs {
+  pub name: String,
+  pub symbol: String,
+  pub uri: String,
+  pub seller_fee_basis_points: u16,
+  pub primary_sale_happened: bool,
+  pub is_mutable: bool,
+  pub edition_nonce: Option<u8>,
+  pub token_standard: Option<TokenStandard>,
+  pub collection: Option<LeafCollection>,
+  pub uses: Option<Uses>,
+  pub creators: Vec<Creators>,
+}
+
+/// Hashes stored in the leaf of a compressed NFT
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LeafHashes {
+  pub data_hash: Node,
+  pub creator_hash: Node,
+  pub asset_hash: Node,
+}
+
+fn write_str(buf: &mut Vec<u8>, value: &str) -> Result<()> {
+  buf.extend_from_slice(&int::<u32, _>(value.len(), "string length")?.to_le_bytes());
+  buf.extend_from_slice(value.as_bytes());
+  Ok(())
+}
+
+fn write_creator(buf: &mut Vec<u8>, creator: &Creators) -> Result<()> {
+  buf.extend_from_slice(parse_pubkey(&creator.address)?.as_ref());
+  buf.push(u8::from(creator.verified));
+  buf.push(int(creator.share, "creator share")?);
+  Ok(())
+}
+
+impl MetadataArgs {
+  /// Borsh encoding, the token program version is always `Original` as Bubblegum does not support Token-2022
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidCompressedAsset`] if a creator address is not a valid pubkey
+  pub fn to_bytes(&self) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    write_str(&mut buf, &self.name)?;
+    write_str(&mut buf, &self.symbol)?;
+    write_str(&mut buf, &self.uri)?;
+    buf.extend_from_slice(&self.seller_fee_basis_points.to_le_bytes());
+    buf.push(u8::from(self.primary_sale_happened));
+    buf.push(u8::from(self.is_mutable));
+    match self.edition_nonce {
+      Some(nonce) => buf.extend_from_slice(&[1, nonce]),
+      None => buf.push(0),
+    }
+    match self.token_standard {
+      Some(standard) => buf.extend_from_slice(&[1, standard as u8]),
+      None => buf.push(0),
+    }
+    match self.collection {
+      Some(collection) => {
+        buf.extend_from_slice(&[1, u8::from(collection.verified)]);
+        buf.extend_from_slice(collection.key.as_ref());
+      },
+      None => buf.push(0),
+    }
+    match &self.uses {
+      Some(uses) => {
+        let method = match uses.use_method {
+          UseMethods::Burn => 0,
+          UseMethods::Multiple => 1,
+          UseMethods::Single => 2,
+        };
+        buf.extend_from_slice(&[1, method]);
+        buf.extend_from_slice(&u64::from(uses.remaining).to_le_bytes());
+        buf.extend_from_slice(&u64::from(uses.total).to_le_bytes());
+      },
+      None => buf.push(0),
+    }
+    // token program version
+    buf.push(0);
+    buf.extend_from_slice(&int::<u32, _>(self.creators.len(), "creators")?.to_le_bytes());
+    for creator in &self.creators {
+      write_creator(&mut buf, creator)?;
+    }
+    Ok(buf)
+  }
+
+  /// `keccak(keccak(metadata) || seller_fee_basis_points)`
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidCompressedAsset`] if a creator address is not a valid pubkey
+  pub fn data_hash(&self) -> Result<Node> {
+    let metadata = hashv(&[&self.to_bytes()?]).to_bytes();
+    Ok(hashv(&[&metadata, &self.seller_fee_basis_points.to_le_bytes()]).to_bytes())
+  }
+}
+
+/// `keccak` of the address, verified flag and share of every creator
+///
+/// # Errors
+///
+/// Will return [`HeliusError::InvalidCompressedAsset`] if a creator address or share is invalid
+pub fn creator_hash(creators: &[Creators]) -> Result<Node> {
+  let mut buf = Vec::with_capacity(creators.len() * 34);
+  for creator in creators {
+    write_creator(&mut buf, creator)?;
+  }
+  Ok(hashv(&[&buf]).to_bytes())
+}
+
+/// Hash of a version 1 `LeafSchema`, this is the leaf stored in the tree
+pub fn asset_hash(
+  id: &Pubkey,
+  owner: &Pubkey,
+  delegate: &Pubkey,
+  nonce: u64,
+  data_hash: &Node,
+  creator_hash: &Node,
+) -> Node {
+  hashv(&[&[1], id.as_ref(), owner.as_ref(), delegate.as_ref(), &nonce.to_le_bytes(), data_hash, creator_hash])
+    .to_bytes()
+}
+
+impl TryFrom<&types::TokenStandard> for TokenStandard {
+  type Error = HeliusError;
+
+  fn try_from(standard: &types::TokenStandard) -> Result<Self> {
+    match standard {
+      types::TokenStandard::NonFungible => Ok(Self::NonFungible),
+      types::TokenStandard::FungibleAsset => Ok(Self::FungibleAsset),
+      types::TokenStandard::Fungible => Ok(Self::Fungible),
+      types::TokenStandard::NonFungibleEdition => Ok(Self::NonFungibleEdition),
+      other => Err(HeliusError::InvalidCompressedAsset { message: format!("token standard {other} is not supported") }),
+    }
+  }
+}
+
+impl GetAssetResponse {
+  /// Rebuild the `MetadataArgs` of a compressed NFT from its content, royalty, supply, grouping and creators
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidCompressedAsset`] if a field does not fit in `MetadataArgs`,
+  /// or the token standard or the verified flag of the collection is missing
+  pub fn metadata_args(&self) -> Result<MetadataArgs> {
+    let missing =
+      |field: &str| HeliusError::InvalidCompressedAsset { message: format!("asset {} has no {field}", self.id) };
+    let content = self.content.as_ref().ok_or_else(|| missing("content"))?;
+    let royalty = self.royalty.as_ref().ok_or_else(|| missing("royalty"))?;
+    let collection = self
+      .grouping
+      .iter()
+      .flatten()
+      .find(|g| g.group_key == "collection")
+      .map(|g| {
+        Ok::<_, HeliusError>(LeafCollection {
+          key: parse_pubkey(&g.group_value)?,
+          verified: g.verified.ok_or_else(|| missing("collection verified flag"))?,
+        })
+      })
+      .transpose()?;
+    Ok(MetadataArgs {
+      name: content.metadata.name.clone(),
+      symbol: content.metadata.symbol.clone(),
+      uri: content.json_uri.clone(),
+      seller_fee_basis_points: int(royalty.basis_points, "seller fee basis points")?,
+      primary_sale_happened: royalty.primary_sale_happened,
+      is_mutable: self.mutable,
+      edition_nonce: self.supply.as_ref().and_then(|s| s.edition_nonce).map(|n| int(n, "edition nonce")).transpose()?,
+      token_standard: Some(
+        content.metadata.token_standard.as_ref().ok_or_else(|| missing("token standard"))?.try_into()?,
+      ),
+      collection,
+      uses: self.uses.clone(),
+      creators: self.creators.clone().unwrap_or_default(),
+    })
+  }
+
+  /// Recompute the leaf hashes from the metadata and ownership of the asset
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidCompressedAsset`] if the asset is not compressed or a field is invalid
+  pub fn compute_hashes(&self) -> Result<LeafHashes> {
+    let compression =
+      self.compression.as_ref().filter(|c| c.compressed).ok_or_else(|| HeliusError::InvalidCompressedAsset {
+        message: format!("asset {} is not compressed", self.id),
+      })?;
+    let owner = parse_pubkey(&self.ownership.owner)?;
+    let delegate = self.ownership.delegate.as_deref().map_or(Ok(owner), parse_pubkey)?;
+    let data_hash = self.metadata_args()?.data_hash()?;
+    let creator_hash = creator_hash(self.creators.as_deref().unwrap_or_default())?;
+    let asset_hash = asset_hash(
+      &parse_pubkey(&self.id)?,
+      &owner,
+      &delegate,
+      u64::from(compression.leaf_id),
+      &data_hash,
+      &creator_hash,
+    );
+    Ok(LeafHashes { data_hash, creator_hash, asset_hash })
+  }
+
+  /// Check the hashes reported in `compression` against the metadata and ownership of the asset
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidCompressedAsset`] if the asset is not compressed or a field is invalid,
+  /// [`HeliusError::InvalidProof`] if a reported hash cannot be decoded
+  pub fn verify_hashes(&self) -> Result<bool> {
+    let hashes = self.compute_hashes()?;
+    let Some(compression) = &self.compression else { return Ok(false) };
+    Ok(
+      hashes.data_hash == decode_node(&compression.data_hash)?
+        && hashes.creator_hash == decode_node(&compression.creator_hash)?
+        && hashes.asset_hash == decode_node(&compression.asset_hash)?,
+    )
+  }
+}
+
+impl GetAssetProofResponse {
+  /// Position of the leaf among the leaves of the tree, `node_index` counts all nodes of the tree
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidProof`] if `node_index` is not a leaf of a tree as deep as the proof
+  pub fn leaf_index(&self) -> Result<u32> {
+    let leaves = u32::try_from(self.proof.len()).ok().and_then(|depth| 1_u32.checked_shl(depth));
+    leaves.and_then(|leaves| self.node_index.checked_sub(leaves)).ok_or_else(|| HeliusError::InvalidProof {
+      message: format!("node index {} does not match a proof of depth {}", self.node_index, self.proof.len()),
+    })
+  }
+
+  /// Recompute the root from `leaf` and `proof`
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidProof`] if the proof cannot be decoded
+  pub fn compute_root(&self) -> Result<Node> {
+    let proof = self.proof.iter().map(|p| decode_node(p)).collect::<Result<Vec<_>>>()?;
+    Ok(recompute_root(decode_node(&self.leaf)?, &proof, self.leaf_index()?))
+  }
+
+  /// Check that the proof leads from `leaf` to `root`
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::InvalidProof`] if the proof cannot be decoded
+  pub fn verify(&self) -> Result<bool> {
+    Ok(self.compute_root()? == decode_node(&self.root)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::{
+    Compression, Content, Creators, GetAssetProofResponse, GetAssetResponse, Grouping, Metadata, Ownership, Royalty,
+    Uses,
+  };
+  use crate::api::types::{self, RoyaltyModel, UseMethods};
+  use crate::compression::{
+    asset_hash, creator_hash, decode_node, recompute_root, LeafCollection, MetadataArgs, Node, TokenStandard,
+  };
+  use crate::error::HeliusError;
+  use solana_sdk::keccak::hashv;
+  use solana_sdk::pubkey::Pubkey;
+  use std::fmt::Write;
+
+  /// Tree of depth 3 with the leaves `[1; 32]` to `[8; 32]`
+  const ROOT: &str = "4YiosUF4mWHi6agrEMFhEsYnVMTuqSxfCgLSGPXTBYdv";
+
+  fn leaves() -> Vec<Node> {
+    (1..=8).map(|i| [i; 32]).collect()
+  }
+
+  /// Levels of the tree, from the leaves to the root
+  fn tree(leaves: Vec<Node>) -> Vec<Vec<Node>> {
+    let mut levels = vec![leaves];
+    while levels[levels.len() - 1].len() > 1 {
+      let next = levels[levels.len() - 1].chunks(2).map(|pair| hashv(&[&pair[0], &pair[1]]).to_bytes()).collect();
+      levels.push(next);
+    }
+    levels
+  }
+
+  fn proof(levels: &[Vec<Node>], index: usize) -> Vec<Node> {
+    levels[..levels.len() - 1].iter().enumerate().map(|(level, nodes)| nodes[(index >> level) ^ 1]).collect()
+  }
+
+  fn encode(node: &Node) -> String {
+    bs58::encode(node).into_string()
+  }
+
+  fn response(index: usize) -> GetAssetProofResponse {
+    let levels = tree(leaves());
+    GetAssetProofResponse {
+      root: String::from(ROOT),
+      proof: proof(&levels, index).iter().map(encode).collect(),
+      node_index: u32::try_from(8 + index).unwrap_or_default(),
+      leaf: encode(&levels[0][index]),
+      tree_id: String::new(),
+    }
+  }
+
+  #[test]
+  fn recompute_fixed_root() -> color_eyre::Result<()> {
+    let levels = tree(leaves());
+    assert_eq!(encode(&levels[3][0]), ROOT);
+    for index in 0..8 {
+      let root = recompute_root(levels[0][index], &proof(&levels, index), u32::try_from(index)?);
+      assert_eq!(root, decode_node(ROOT)?);
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn recompute_empty_tree() {
+    // roots of empty keccak trees of depth 1 and 2, shared with other keccak sparse Merkle trees
+    let depth_1 = "ad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5";
+    let depth_2 = "b4c11951957c6f8f642c4af61cd6b24640fec6dc7fc607ee8206a99e92410d30";
+    let hex = |node: Node| {
+      node.iter().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+      })
+    };
+    assert_eq!(hex(recompute_root([0; 32], &[[0; 32]], 0)), depth_1);
+    let sibling = recompute_root([0; 32], &[[0; 32]], 0);
+    assert_eq!(hex(recompute_root([0; 32], &[[0; 32], sibling], 3)), depth_2);
+  }
+
+  #[test]
+  fn verify_proof() -> color_eyre::Result<()> {
+    for index in 0..8 {
+      let res = response(index);
+      assert_eq!(res.leaf_index()?, u32::try_from(index)?);
+      assert!(res.verify()?);
+    }
+
+    let mut res = response(5);
+    res.leaf = encode(&[9; 32]);
+    assert!(!res.verify()?);
+
+    let mut res = response(5);
+    res.node_index = 12;
+    assert!(!res.verify()?);
+
+    let mut res = response(5);
+    res.proof.swap(0, 1);
+    assert!(!res.verify()?);
+    Ok(())
+  }
+
+  #[test]
+  fn invalid_proof() {
+    let mut res = response(0);
+    res.node_index = 7;
+    assert!(matches!(res.leaf_index(), Err(HeliusError::InvalidProof { .. })));
+    res.node_index = 8;
+    res.root = String::from("0OIl");
+    assert!(matches!(res.verify(), Err(HeliusError::InvalidProof { .. })));
+    res.root = encode(&[1; 32])[..10].to_string();
+    assert!(matches!(res.verify(), Err(HeliusError::InvalidProof { .. })));
+  }
+
+  fn creators() -> Vec<Creators> {
+    vec![
+      Creators { address: Pubkey::new_from_array([7; 32]).to_string(), share: 60, verified: true },
+      Creators { address: Pubkey::new_from_array([8; 32]).to_string(), share: 40, verified: false },
+    ]
+  }
+
+  fn compressed_asset() -> GetAssetResponse {
+    GetAssetResponse {
+      id: Pubkey::new_from_array([1; 32]).to_string(),
+      content: Some(Content {
+        json_uri: String::from("https://example.com/1.json"),
+        metadata: Metadata {
+          name: String::from("cNFT #1"),
+          symbol: String::from("CNFT"),
+          token_standard: Some(types::TokenStandard::NonFungible),
+          ..Default::default()
+        },
+        ..Default::default()
+      }),
+      compression: Some(Compression { compressed: true, leaf_id: 3, ..Default::default() }),
+      grouping: Some(vec![Grouping {
+        group_key: String::from("collection"),
+        group_value: Pubkey::new_from_array([9; 32]).to_string(),
+        verified: Some(true),
+        ..Default::default()
+      }]),
+      royalty: Some(Royalty {
+        royalty_model: RoyaltyModel::Creators,
+        target: None,
+        percent: 0.05,
+        basis_points: 500,
+        primary_sale_happened: false,
+        locked: false,
+      }),
+      ownership: Ownership { owner: Pubkey::new_from_array([2; 32]).to_string(), ..Default::default() },
+      creators: Some(creators()),
+      mutable: true,
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn creator_hash_encoding() -> color_eyre::Result<()> {
+    // keccak of no input
+    let empty = "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470";
+    let hex = creator_hash(&[])?.iter().fold(String::new(), |mut hex, b| {
+      let _ = write!(hex, "{b:02x}");
+      hex
+    });
+    assert_eq!(hex, empty);
+
+    let mut bytes = [7; 32].to_vec();
+    bytes.extend([1, 60]);
+    bytes.extend([8; 32]);
+    bytes.extend([0, 40]);
+    assert_eq!(creator_hash(&creators())?, hashv(&[&bytes]).to_bytes());
+
+    let mut invalid = creators();
+    invalid[0].share = 256;
+    assert!(matches!(creator_hash(&invalid), Err(HeliusError::InvalidCompressedAsset { .. })));
+    Ok(())
+  }
+
+  #[test]
+  fn metadata_encoding() -> color_eyre::Result<()> {
+    let args = MetadataArgs {
+      name: String::from("a"),
+      symbol: String::new(),
+      uri: String::from("u"),
+      seller_fee_basis_points: 258,
+      primary_sale_happened: true,
+      is_mutable: false,
+      edition_nonce: Some(4),
+      token_standard: Some(TokenStandard::NonFungible),
+      collection: Some(LeafCollection { key: Pubkey::new_from_array([9; 32]), verified: true }),
+      uses: Some(Uses { use_method: UseMethods::Single, remaining: 1, total: 2 }),
+      creators: creators()[..1].to_vec(),
+    };
+    let mut bytes = vec![1, 0, 0, 0, b'a', 0, 0, 0, 0, 1, 0, 0, 0, b'u', 2, 1, 1, 0, 1, 4, 1, 0, 1, 1];
+    bytes.extend([9; 32]);
+    bytes.extend([1, 2, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+    bytes.extend([0, 1, 0, 0, 0]);
+    bytes.extend([7; 32]);
+    bytes.extend([1, 60]);
+    assert_eq!(args.to_bytes()?, bytes);
+
+    let metadata = hashv(&[&bytes]).to_bytes();
+    assert_eq!(args.data_hash()?, hashv(&[&metadata, &[2, 1]]).to_bytes());
+    Ok(())
+  }
+
+  #[test]
+  fn verify_asset_hashes() -> color_eyre::Result<()> {
+    let mut asset = compressed_asset();
+    let args = asset.metadata_args()?;
+    assert_eq!(args.seller_fee_basis_points, 500);
+    assert_eq!(args.token_standard, Some(TokenStandard::NonFungible));
+    assert_eq!(args.collection, Some(LeafCollection { key: Pubkey::new_from_array([9; 32]), verified: true }));
+
+    let hashes = asset.compute_hashes()?;
+    let owner = Pubkey::new_from_array([2; 32]);
+    let expected =
+      asset_hash(&Pubkey::new_from_array([1; 32]), &owner, &owner, 3, &hashes.data_hash, &hashes.creator_hash);
+    assert_eq!(hashes.asset_hash, expected);
+
+    let compression = asset.compression.as_mut().ok_or_else(|| color_eyre::eyre::eyre!("compression"))?;
+    compression.data_hash = encode(&hashes.data_hash);
+    compression.creator_hash = encode(&hashes.creator_hash);
+    compression.asset_hash = encode(&hashes.asset_hash);
+    assert!(asset.verify_hashes()?);
+
+    let mut stale = asset.clone();
+    if let Some(content) = stale.content.as_mut() {
+      content.metadata.name = String::from("cNFT #2");
+    }
+    assert!(!stale.verify_hashes()?);
+
+    let mut delegated = asset.clone();
+    delegated.ownership.delegate = Some(Pubkey::new_from_array([5; 32]).to_string());
+    assert!(!delegated.verify_hashes()?);
+
+    let mut uncompressed = asset;
+    uncompressed.compression = None;
+    assert!(matches!(uncompressed.verify_hashes(), Err(HeliusError::InvalidCompressedAsset { .. })));
+    Ok(())
+  }
+
+  #[test]
+  fn metadata_args_from_response() {
+    let mut asset = compressed_asset();
+    if let Some(content) = asset.content.as_mut() {
+      content.metadata.token_standard = Some(types::TokenStandard::NonFungibleEdition);
+    }
+    assert!(
+      matches!(asset.metadata_args(), Ok(args) if args.token_standard == Some(TokenStandard::NonFungibleEdition))
+    );
+    if let Some(content) = asset.content.as_mut() {
+      content.metadata.token_standard = Some(types::TokenStandard::ProgrammableNonFungible);
+    }
+    assert!(matches!(asset.metadata_args(), Err(HeliusError::InvalidCompressedAsset { .. })));
+    if let Some(content) = asset.content.as_mut() {
+      content.metadata.token_standard = None;
+    }
+    assert!(matches!(asset.metadata_args(), Err(HeliusError::InvalidCompressedAsset { .. })));
+
+    let mut unverified = compressed_asset();
+    if let Some(group) = unverified.grouping.iter_mut().flatten().next() {
+      group.verified = Some(false);
+    }
+    assert!(matches!(unverified.metadata_args(), Ok(args) if args.collection.is_some_and(|c| !c.verified)));
+    if let Some(group) = unverified.grouping.iter_mut().flatten().next() {
+      group.verified = None;
+    }
+    assert!(matches!(unverified.metadata_args(), Err(HeliusError::InvalidCompressedAsset { .. })));
+  }
+}
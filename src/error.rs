@@ -1,6 +1,11 @@
+use crate::retry::RetryOn;
+use crate::secret::{without_api_key, without_api_key_param, ApiKey};
 use reqwest::StatusCode;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_request::RpcError;
+use std::time::Duration;
 use thiserror::Error;
-use url::ParseError;
+use url::{ParseError, Url};
 
 #[derive(Debug, Error)]
 pub enum HeliusError {
@@ -10,7 +15,7 @@ pub enum HeliusError {
 
   #[error(transparent)]
   /// Thrown when submitting a POST/GET request fails
-  ReqwestError(#[from] reqwest::Error),
+  ReqwestError(reqwest::Error),
 
   #[error(transparent)]
   UrlError(#[from] ParseError),
@@ -33,18 +38,402 @@ pub enum HeliusError {
   #[error("RPC Error  code:{code} message:{message}")]
   RpcError { code: i32, message: String },
 
+  #[error("Transaction {signature} could not be parsed")]
+  TransactionNotParsed { signature: String },
+
+  /// Helius searched part of the history without finding a matching transaction,
+  /// the search continues with `before` set to the given signature
+  #[error("No transactions found in the search period, continue before {before}")]
+  TransactionHistoryGap { before: String },
+
+  #[error("No response for batch request id {id}")]
+  MissingBatchResponse { id: String },
+
   #[error("Invalid cluster:{message}")]
   InvalidCluster { message: String },
 
+  /// Boxed to keep `Result<T, HeliusError>` small
   #[error(transparent)]
-  SolanaClientError(#[from] solana_client::client_error::ClientError),
+  SolanaClientError(Box<ClientError>),
 
+  /// Use [`HeliusError::retry_after`] for the `Retry-After` delay, more fields may be added
   #[error("Too Many Requests: {path}")]
-  TooManyRequests { path: String },
+  #[non_exhaustive]
+  TooManyRequests { path: String, retry_after: Option<Duration> },
+
+  #[error("Invalid asset proof: {message}")]
+  InvalidProof { message: String },
+
+  #[error("Invalid base58 value {value}: {message}")]
+  InvalidBase58 { value: String, message: String },
+
+  #[error("Invalid compressed asset: {message}")]
+  InvalidCompressedAsset { message: String },
 
   #[error("Invalid fee response type {response}")]
   InvalidFeeResponse { response: String },
 
   #[error(transparent)]
   TransactionEncodeError(#[from] bincode::Error),
+
+  #[error(transparent)]
+  Io(#[from] std::io::Error),
+
+  #[error(transparent)]
+  SignerError(#[from] solana_sdk::signer::SignerError),
+
+  #[error("Invalid transaction: {message}")]
+  InvalidTransaction { message: String },
+
+  #[error("Invalid webhook: {message}")]
+  InvalidWebhook { message: String },
+
+  #[error("Transaction {signature} failed: {err}")]
+  TransactionFailed { signature: String, err: solana_sdk::transaction::TransactionError },
+
+  #[error("Transaction {signature} was not confirmed before its blockhash expired")]
+  TransactionExpired { signature: String },
+
+  #[error("No recorded response for {method} {url}")]
+  NoRecordedResponse { method: String, url: String },
+}
+
+/// Broad class of a [`HeliusError`], see [`HeliusError::kind`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+  /// No response was received, the connection failed or timed out
+  Transport,
+  /// HTTP 429
+  RateLimited,
+  /// The API key is missing, invalid or not allowed to use the endpoint (HTTP 401, 403)
+  Auth,
+  /// The resource does not exist (HTTP 404, asset not found)
+  NotFound,
+  /// The request was rejected as invalid, usually a bug in the caller (HTTP 4xx, invalid params, invalid input)
+  InvalidRequest,
+  /// The server failed to handle the request (HTTP 5xx)
+  Server,
+  /// The response could not be decoded
+  Decode,
+  /// A transaction failed or expired
+  Transaction,
+  Other,
+}
+
+impl ErrorKind {
+  fn from_status(status: StatusCode) -> Self {
+    match status {
+      StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => Self::Auth,
+      StatusCode::NOT_FOUND => Self::NotFound,
+      StatusCode::TOO_MANY_REQUESTS => Self::RateLimited,
+      s if s.is_client_error() => Self::InvalidRequest,
+      s if s.is_server_error() => Self::Server,
+      _ => Self::Other,
+    }
+  }
+
+  fn from_reqwest(err: &reqwest::Error) -> Self {
+    match err.status() {
+      Some(status) => Self::from_status(status),
+      None if err.is_decode() => Self::Decode,
+      None if err.is_builder() => Self::InvalidRequest,
+      None => Self::Transport,
+    }
+  }
+}
+
+/// Named JSON-RPC errors of the DAS API, see [`HeliusError::das_error`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DasError {
+  /// -32700
+  ParseError,
+  /// -32600
+  InvalidRequest,
+  /// -32601
+  MethodNotFound,
+  /// -32602
+  InvalidParams,
+  /// -32603
+  Internal,
+  /// -32000 with a not found message, DAS reports every server error with -32000
+  AssetNotFound,
+  /// Other -32000 to -32099 server errors
+  Server(i32),
+  Other(i32),
+}
+
+impl DasError {
+  pub fn from_rpc(code: i32, message: &str) -> Self {
+    match code {
+      -32700 => Self::ParseError,
+      -32600 => Self::InvalidRequest,
+      -32601 => Self::MethodNotFound,
+      -32602 => Self::InvalidParams,
+      -32603 => Self::Internal,
+      -32000 if message.to_lowercase().replace(' ', "").contains("notfound") => Self::AssetNotFound,
+      -32099..=-32000 => Self::Server(code),
+      _ => Self::Other(code),
+    }
+  }
+
+  pub const fn kind(self) -> ErrorKind {
+    match self {
+      Self::ParseError | Self::InvalidRequest | Self::MethodNotFound | Self::InvalidParams => ErrorKind::InvalidRequest,
+      Self::Internal | Self::Server(_) => ErrorKind::Server,
+      Self::AssetNotFound => ErrorKind::NotFound,
+      Self::Other(_) => ErrorKind::Other,
+    }
+  }
+}
+
+impl HeliusError {
+  pub fn kind(&self) -> ErrorKind {
+    match self {
+      Self::ReqwestError(e) => ErrorKind::from_reqwest(e),
+      Self::SolanaClientError(e) => match e.kind() {
+        ClientErrorKind::Io(_) => ErrorKind::Transport,
+        ClientErrorKind::Reqwest(e) => match e.status() {
+          Some(status) => StatusCode::from_u16(status.as_u16()).map_or(ErrorKind::Other, ErrorKind::from_status),
+          None if e.is_decode() => ErrorKind::Decode,
+          None => ErrorKind::Transport,
+        },
+        ClientErrorKind::SerdeJson(_) => ErrorKind::Decode,
+        ClientErrorKind::SigningError(_) => ErrorKind::InvalidRequest,
+        ClientErrorKind::TransactionError(_) => ErrorKind::Transaction,
+        ClientErrorKind::RpcError(_) | ClientErrorKind::Custom(_) => ErrorKind::Other,
+      },
+      Self::InternalError { code, .. } | Self::Unknown { code, .. } => ErrorKind::from_status(*code),
+      Self::NotFound { .. } | Self::TransactionNotParsed { .. } | Self::TransactionHistoryGap { .. } => {
+        ErrorKind::NotFound
+      },
+      Self::Unauthorized { .. } => ErrorKind::Auth,
+      Self::TooManyRequests { .. } => ErrorKind::RateLimited,
+      Self::RpcError { code, message } => DasError::from_rpc(*code, message).kind(),
+      Self::SerdeJson { .. } | Self::MissingBatchResponse { .. } | Self::InvalidFeeResponse { .. } => ErrorKind::Decode,
+      Self::BadRequest { .. }
+      | Self::UrlError(_)
+      | Self::InvalidCluster { .. }
+      | Self::InvalidProof { .. }
+      | Self::InvalidBase58 { .. }
+      | Self::InvalidCompressedAsset { .. }
+      | Self::TransactionEncodeError(_)
+      | Self::SignerError(_)
+      | Self::InvalidTransaction { .. }
+      | Self::InvalidWebhook { .. } => ErrorKind::InvalidRequest,
+      Self::TransactionFailed { .. } | Self::TransactionExpired { .. } => ErrorKind::Transaction,
+      Self::Io(_) | Self::NoRecordedResponse { .. } => ErrorKind::Other,
+    }
+  }
+
+  /// Name of the variant, e.g. `"TooManyRequests"`
+  pub const fn variant_name(&self) -> &'static str {
+    match self {
+      Self::SerdeJson { .. } => "SerdeJson",
+      Self::ReqwestError(_) => "ReqwestError",
+      Self::UrlError(_) => "UrlError",
+      Self::InternalError { .. } => "InternalError",
+      Self::NotFound { .. } => "NotFound",
+      Self::BadRequest { .. } => "BadRequest",
+      Self::Unauthorized { .. } => "Unauthorized",
+      Self::Unknown { .. } => "Unknown",
+      Self::RpcError { .. } => "RpcError",
+      Self::TransactionNotParsed { .. } => "TransactionNotParsed",
+      Self::TransactionHistoryGap { .. } => "TransactionHistoryGap",
+      Self::MissingBatchResponse { .. } => "MissingBatchResponse",
+      Self::InvalidCluster { .. } => "InvalidCluster",
+      Self::SolanaClientError(_) => "SolanaClientError",
+      Self::TooManyRequests { .. } => "TooManyRequests",
+      Self::InvalidProof { .. } => "InvalidProof",
+      Self::InvalidBase58 { .. } => "InvalidBase58",
+      Self::InvalidCompressedAsset { .. } => "InvalidCompressedAsset",
+      Self::InvalidFeeResponse { .. } => "InvalidFeeResponse",
+      Self::TransactionEncodeError(_) => "TransactionEncodeError",
+      Self::Io(_) => "Io",
+      Self::SignerError(_) => "SignerError",
+      Self::InvalidTransaction { .. } => "InvalidTransaction",
+      Self::InvalidWebhook { .. } => "InvalidWebhook",
+      Self::TransactionFailed { .. } => "TransactionFailed",
+      Self::TransactionExpired { .. } => "TransactionExpired",
+      Self::NoRecordedResponse { .. } => "NoRecordedResponse",
+    }
+  }
+
+  /// HTTP status of the response the error was built from
+  pub fn status(&self) -> Option<StatusCode> {
+    match self {
+      Self::ReqwestError(e) => e.status(),
+      Self::SolanaClientError(e) => match e.kind() {
+        ClientErrorKind::Reqwest(e) => e.status().and_then(|s| StatusCode::from_u16(s.as_u16()).ok()),
+        _ => None,
+      },
+      Self::InternalError { code, .. } | Self::Unknown { code, .. } => Some(*code),
+      Self::NotFound { .. } => Some(StatusCode::NOT_FOUND),
+      Self::BadRequest { .. } => Some(StatusCode::BAD_REQUEST),
+      Self::Unauthorized { .. } => Some(StatusCode::UNAUTHORIZED),
+      Self::TooManyRequests { .. } => Some(StatusCode::TOO_MANY_REQUESTS),
+      _ => None,
+    }
+  }
+
+  /// Named DAS error of a JSON-RPC error response
+  pub fn das_error(&self) -> Option<DasError> {
+    match self {
+      Self::RpcError { code, message } => Some(DasError::from_rpc(*code, message)),
+      _ => None,
+    }
+  }
+
+  /// `Retry-After` delay of a 429 response
+  pub const fn retry_after(&self) -> Option<Duration> {
+    match self {
+      Self::TooManyRequests { retry_after, .. } => *retry_after,
+      _ => None,
+    }
+  }
+
+  /// Rate limits, server errors and transport failures may succeed when an idempotent request is retried,
+  /// see [`RetryOn`] for the errors retried on other requests
+  pub fn is_retryable(&self) -> bool {
+    RetryOn::ALL.iter().any(|r| r.matches(self, true))
+  }
+
+  pub fn is_rate_limited(&self) -> bool {
+    self.kind() == ErrorKind::RateLimited
+  }
+
+  pub fn is_auth(&self) -> bool {
+    self.kind() == ErrorKind::Auth
+  }
+
+  /// The request itself is invalid and will fail again if retried unchanged
+  pub fn is_client_error(&self) -> bool {
+    self.kind() == ErrorKind::InvalidRequest
+  }
+
+  /// Replace every occurrence of `key` in the message of this error, urls of transport errors included
+  #[must_use]
+  pub fn redact(self, key: &ApiKey) -> Self {
+    match self {
+      Self::ReqwestError(mut e) => {
+        if let Some(url) = e.url_mut() {
+          key.redact_url(url);
+        }
+        Self::ReqwestError(e)
+      },
+      Self::SolanaClientError(mut e) => {
+        redact_client_error(&mut e.kind, &|text| key.redact(&without_api_key_param(text)), &|url| key.redact_url(url));
+        Self::SolanaClientError(e)
+      },
+      Self::SerdeJson { err, text } => Self::SerdeJson { err, text: key.redact(&text) },
+      Self::InternalError { code, text } => Self::InternalError { code, text: key.redact(&text) },
+      Self::Unknown { code, text } => Self::Unknown { code, text: key.redact(&text) },
+      Self::NotFound { path } => Self::NotFound { path: key.redact(&path) },
+      Self::BadRequest { path, text } => Self::BadRequest { path: key.redact(&path), text: key.redact(&text) },
+      Self::Unauthorized { path, text } => Self::Unauthorized { path: key.redact(&path), text: key.redact(&text) },
+      Self::TooManyRequests { path, retry_after } => Self::TooManyRequests { path: key.redact(&path), retry_after },
+      Self::RpcError { code, message } => Self::RpcError { code, message: key.redact(&message) },
+      Self::InvalidCluster { message } => Self::InvalidCluster { message: key.redact(&message) },
+      Self::NoRecordedResponse { method, url } => Self::NoRecordedResponse { method, url: key.redact(&url) },
+      other => other,
+    }
+  }
+}
+
+/// The `api-key` query parameter is removed from the url of the error
+impl From<reqwest::Error> for HeliusError {
+  fn from(mut err: reqwest::Error) -> Self {
+    if let Some(url) = err.url_mut() {
+      *url = without_api_key(url);
+    }
+    Self::ReqwestError(err)
+  }
+}
+
+/// Solana client errors carry the url in a [`reqwest::Error`] or in the text of the message
+fn redact_client_error(kind: &mut ClientErrorKind, text: &dyn Fn(&str) -> String, url: &dyn Fn(&mut Url)) {
+  match kind {
+    ClientErrorKind::Reqwest(e) => {
+      if let Some(u) = e.url_mut() {
+        url(u);
+      }
+    },
+    ClientErrorKind::Custom(message)
+    | ClientErrorKind::RpcError(RpcError::RpcRequestError(message) | RpcError::ForUser(message)) => {
+      *message = text(message);
+    },
+    _ => {},
+  }
+}
+
+/// The `api-key` query parameter is removed from the url and messages of the error
+impl From<ClientError> for HeliusError {
+  fn from(mut err: ClientError) -> Self {
+    redact_client_error(&mut err.kind, &without_api_key_param, &|url| *url = without_api_key(url));
+    Self::SolanaClientError(Box::new(err))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::error::{DasError, ErrorKind, HeliusError};
+  use reqwest::StatusCode;
+
+  #[test]
+  fn http_errors() {
+    let path = String::from("/");
+    let text = String::new();
+    let cases = [
+      (HeliusError::NotFound { path: path.clone() }, ErrorKind::NotFound, StatusCode::NOT_FOUND),
+      (
+        HeliusError::BadRequest { path: path.clone(), text: text.clone() },
+        ErrorKind::InvalidRequest,
+        StatusCode::BAD_REQUEST,
+      ),
+      (HeliusError::Unauthorized { path: path.clone(), text: text.clone() }, ErrorKind::Auth, StatusCode::UNAUTHORIZED),
+      (HeliusError::TooManyRequests { path, retry_after: None }, ErrorKind::RateLimited, StatusCode::TOO_MANY_REQUESTS),
+      (
+        HeliusError::InternalError { code: StatusCode::GATEWAY_TIMEOUT, text: text.clone() },
+        ErrorKind::Server,
+        StatusCode::GATEWAY_TIMEOUT,
+      ),
+      (
+        HeliusError::Unknown { code: StatusCode::FORBIDDEN, text: text.clone() },
+        ErrorKind::Auth,
+        StatusCode::FORBIDDEN,
+      ),
+      (
+        HeliusError::Unknown { code: StatusCode::IM_A_TEAPOT, text },
+        ErrorKind::InvalidRequest,
+        StatusCode::IM_A_TEAPOT,
+      ),
+    ];
+    for (err, kind, status) in cases {
+      assert_eq!(err.kind(), kind, "{err}");
+      assert_eq!(err.status(), Some(status));
+    }
+  }
+
+  #[test]
+  fn retryable() {
+    assert!(HeliusError::TooManyRequests { path: String::new(), retry_after: None }.is_retryable());
+    assert!(HeliusError::InternalError { code: StatusCode::SERVICE_UNAVAILABLE, text: String::new() }.is_retryable());
+    assert!(!HeliusError::BadRequest { path: String::new(), text: String::new() }.is_retryable());
+    assert!(HeliusError::Unauthorized { path: String::new(), text: String::new() }.is_auth());
+    assert!(HeliusError::InvalidCluster { message: String::new() }.is_client_error());
+    assert!(!HeliusError::TransactionExpired { signature: String::new() }.is_retryable());
+  }
+
+  #[test]
+  fn das_errors() {
+    let rpc = |code, message: &str| HeliusError::RpcError { code, message: message.to_string() };
+    assert_eq!(rpc(-32000, "Asset Not Found").das_error(), Some(DasError::AssetNotFound));
+    assert_eq!(rpc(-32000, "Database Error: RecordNotFound").kind(), ErrorKind::NotFound);
+    assert_eq!(rpc(-32000, "Database Error").das_error(), Some(DasError::Server(-32000)));
+    assert_eq!(rpc(-32602, "Invalid params").das_error(), Some(DasError::InvalidParams));
+    assert!(rpc(-32602, "Invalid params").is_client_error());
+    assert_eq!(rpc(-32601, "Method not found").das_error(), Some(DasError::MethodNotFound));
+    assert_eq!(rpc(-32603, "Internal error").kind(), ErrorKind::Server);
+    assert_eq!(rpc(42, "?").das_error(), Some(DasError::Other(42)));
+    assert_eq!(HeliusError::NotFound { path: String::new() }.das_error(), None);
+  }
 }
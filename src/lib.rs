@@ -2,7 +2,7 @@
 //!
 //! `selene_helius_sdk` is an async library for the Helius [SDK](https://docs.helius.dev/)
 //!
-//! ```rust
+//! ```rust,no_run
 //! use color_eyre::Result;
 //! use selene_helius_sdk::api::das::GetAssetsByOwnerParams;
 //! use selene_helius_sdk::HeliusBuilder;
@@ -33,9 +33,22 @@
 //! See [`HeliusBuilder`] for other option such as timeouts and providing your own http client
 //!
 pub mod api;
+pub mod compression;
 pub mod error;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod middleware;
+pub mod rate_limit;
 mod request_handler;
+pub mod retry;
+pub mod secret;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transport;
 pub mod util;
+#[cfg(feature = "webhook-server")]
+pub mod webhook_server;
+pub mod webhook_verifier;
 
 pub type Result<T> = std::result::Result<T, error::HeliusError>;
 
@@ -44,17 +57,39 @@ pub use api::{Helius, HeliusBuilder};
 use serde::Serialize;
 use std::str::FromStr;
 
-#[derive(Clone, Copy, Default, Serialize, PartialEq, Eq, Debug)]
+#[derive(Clone, Default, Serialize, PartialEq, Eq, Debug)]
 pub enum Cluster {
   #[default]
   MainnetBeta,
   Devnet,
+  /// Self-hosted or dedicated endpoints.
+  /// `rpc` is used as is, `api` is the base url of the REST API, e.g. `https://api-mainnet.helius-rpc.com/v0`
+  Custom {
+    rpc: String,
+    api: String,
+  },
 }
 
 impl FromStr for Cluster {
   type Err = HeliusError;
 
+  /// Parse `mainnet` or `devnet`, or a full RPC url.
+  /// Urls of the public Helius RPC hosts parse as [`Cluster::MainnetBeta`] or [`Cluster::Devnet`], so the api key
+  /// of the builder is still appended. Any other url is a [`Cluster::Custom`], whose REST API is Helius devnet if the
+  /// url mentions devnet, mainnet otherwise
   fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+    if let Ok(url) = url::Url::parse(s) {
+      if !matches!(url.scheme(), "http" | "https") || !url.has_host() {
+        return Err(HeliusError::InvalidCluster { message: String::from(s) });
+      }
+      match url.host_str().map(str::to_lowercase).as_deref() {
+        Some("mainnet.helius-rpc.com" | "rpc.helius.xyz") => return Ok(Self::MainnetBeta),
+        Some("devnet.helius-rpc.com" | "rpc-devnet.helius.xyz") => return Ok(Self::Devnet),
+        _ => {},
+      }
+      let api = if s.to_lowercase().contains("devnet") { api::DEV_API_URL_V0 } else { api::API_URL_V0 };
+      return Ok(Self::Custom { rpc: String::from(s), api: String::from(api) });
+    }
     if s.to_lowercase().contains("mainnet") {
       return Ok(Self::MainnetBeta);
     }
@@ -106,6 +141,7 @@ mod tests {
     });
   }
 
+  /// Live client, the tests using it are ignored by default, `mod mock` runs them against [`crate::testing::MockHelius`]
   #[rstest::fixture]
   fn config() -> Config {
     setup();
@@ -138,6 +174,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   #[allow(clippy::unwrap_used)]
   async fn test_enhanced_txn(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
@@ -179,6 +216,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   #[allow(clippy::unwrap_used)]
   async fn test_enhanced_history_txn(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
@@ -193,6 +231,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn webhook(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -236,6 +275,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn test_get_names(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -250,6 +290,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn test_get_asset_nft(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -266,6 +307,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_asset_fungible(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -283,6 +325,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_asset_inscription(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -300,6 +343,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_asset_non_exist_mint(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -314,6 +358,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_asset_batch(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -333,6 +378,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_asset_by_owner(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -354,6 +400,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_asset_by_non_exist_owner(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -375,6 +422,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn asset_by_authority(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -394,6 +442,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_asset_by_creator(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -415,6 +464,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn search_asset(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -441,6 +491,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn search_asset_token_info(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -472,6 +523,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn asset_groups(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -490,6 +542,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn asset_proof(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -502,6 +555,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn asset_proof_batch(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -514,6 +568,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_token_accounts_owner(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -530,6 +585,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_token_accounts_mint(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -546,6 +602,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_estimate_fee(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -564,6 +621,7 @@ mod tests {
 
   #[rstest::rstest]
   #[tokio::test]
+  #[ignore = "calls the Helius API, run with HELIUS_API_KEY and --ignored"]
   async fn get_estimate_fee_transaction(config: Config) -> color_eyre::Result<()> {
     if config.client.is_none() {
       return Ok(());
@@ -590,6 +648,357 @@ mod tests {
     }
   }
 
+  #[cfg(feature = "testing")]
+  mod mock {
+    use crate::api::das::{
+      DisplayOptions, GetAssetBatchParams, GetAssetParams, GetAssetProofBatchParams, GetAssetProofParams,
+      GetAssetProofResponse, GetAssetResponse, GetAssetsByAuthorityParams, GetAssetsByCreatorParams,
+      GetAssetsByGroupParams, GetAssetsByOwnerParams, GetTokenAccountsParams, Ownership, Pagination, PriceInfo,
+      PriorityLevel, SearchAssetDisplayOptions, SearchAssetsParams, TokenInfo,
+    };
+    use crate::api::types::{AccountWebhookEncoding, TokenType, TransactionType, TxnStatus};
+    use crate::api::webhook::{CreateWebhookRequest, EditWebhookRequest, WebhookData, WebhookType};
+    use crate::api::Helius;
+    use crate::testing::MockHelius;
+    use bigdecimal::{BigDecimal, Zero};
+    use color_eyre::eyre::format_err;
+
+    const MAD_LAD: &str = "F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk";
+    const MAD_LAD_OWNER: &str = "4zdNGgAtFsW1cQgHqkiWyRsxaAgxrSRRynnuunxzjxue";
+    const TOLY: &str = "86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY";
+
+    async fn mock() -> color_eyre::Result<(MockHelius, Helius)> {
+      super::setup();
+      let mock = MockHelius::start().await?;
+      let client = mock.builder().build()?;
+      Ok((mock, client))
+    }
+
+    fn asset(id: &str, owner: &str) -> GetAssetResponse {
+      GetAssetResponse {
+        id: id.to_string(),
+        ownership: Ownership { owner: owner.to_string(), ..Default::default() },
+        ..Default::default()
+      }
+    }
+
+    #[tokio::test]
+    async fn test_enhanced_history_txn() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let res = client.parsed_transaction_history("CKs1E69a2e9TmH4mKKLrXFF8kD3ZnwKjoEuXa6sz9WqX").await?;
+      assert!(!res.is_empty());
+      assert!(res[0].timestamp > 0);
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn webhook() -> color_eyre::Result<()> {
+      let (mock, client) = mock().await?;
+      let req = CreateWebhookRequest {
+        data: WebhookData {
+          webhook_url: "https://localhost:3000".to_string(),
+          transaction_types: TransactionType::all(),
+          account_addresses: vec!["AKo9P7S8FE9NYeAcrtZEpimwQAXJMp8Lrt8p4dMkHkY2".to_string()],
+          webhook_type: WebhookType::Enhanced,
+          auth_header: None,
+          txn_status: TxnStatus::All,
+          encoding: AccountWebhookEncoding::JsonParsed,
+        },
+      };
+      let hook = client.create_webhook(&req).await?;
+      assert_eq!(client.get_all_webhooks().await?.len(), 1);
+      let mut hooky = client.get_webhook_by_id(hook.webhook_id.as_str()).await?;
+      hooky.webhook_data.transaction_types.push(TransactionType::Fuse);
+      let edited_hook = client
+        .edit_webhook(&EditWebhookRequest { webhook_id: hooky.webhook_id.clone(), data: hooky.webhook_data })
+        .await?;
+      assert_eq!(edited_hook.webhook_data.transaction_types.last(), Some(&TransactionType::Fuse));
+
+      let add_addr = vec!["BKo9P7S8FE9NYeAcrtZEpimwQAXJMp8Lrt8p4dMkHkY2".to_owned()];
+      client.append_addresses_to_webhook(&hooky.webhook_id, &add_addr).await?;
+      assert_eq!(mock.webhooks()[0].webhook_data.account_addresses.len(), 2);
+      client.delete_webhook(&edited_hook.webhook_id).await?;
+      if !mock.webhooks().is_empty() {
+        return Err(format_err!("hook not deleted"));
+      }
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_names() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let result = client.get_names(TOLY).await?;
+      assert!(!result.domain_names.is_empty());
+      let r = result.domain_names.into_iter().find(|d| d == "toly");
+      assert!(r.is_some());
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_asset_nft() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let mad_libs = String::from(MAD_LAD);
+      let res = client
+        .get_asset(&GetAssetParams { id: mad_libs.clone(), display_options: None })
+        .await?
+        .expect("mad_lib address should exists");
+      assert_eq!(res.id, mad_libs);
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_asset_fungible() -> color_eyre::Result<()> {
+      let (mock, client) = mock().await?;
+      let jito = String::from("J1toso1uCk3RLmjorhTtrVwY9HJ7X8V9yYac6Y7kGCPn");
+      mock.add_asset(asset(&jito, TOLY));
+      let opts = DisplayOptions { show_fungible: true, show_inscription: false };
+      let res = client
+        .get_asset(&GetAssetParams { id: jito.clone(), display_options: Some(opts) })
+        .await?
+        .expect("jito address should exists");
+      assert_eq!(res.id, jito);
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_asset_inscription() -> color_eyre::Result<()> {
+      let (mock, client) = mock().await?;
+      let rando = String::from("AKo9P7S8FE9NYeAcrtZEpimwQAXJMp8Lrt8p4dMkHkY2");
+      mock.add_asset(asset(&rando, TOLY));
+      let opts = DisplayOptions { show_fungible: false, show_inscription: true };
+      let res = client
+        .get_asset(&GetAssetParams { id: rando.clone(), display_options: Some(opts) })
+        .await?
+        .expect("rando address should exists");
+      assert_eq!(res.id, rando);
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_asset_non_exist_mint() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let non_exist_mint = String::from("HXyEGGvDDJvXuYkvD7cwYSwrSPKvqn54BY3BpqGVozei");
+      let opts = DisplayOptions { show_fungible: true, show_inscription: true };
+      let res = client.get_asset(&GetAssetParams { id: non_exist_mint.clone(), display_options: Some(opts) }).await?;
+      assert_eq!(res, None);
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_asset_batch() -> color_eyre::Result<()> {
+      let (mock, client) = mock().await?;
+      let ids: Vec<String> = vec![
+        "81bxPqYCE8j34nQm7Rooqi8Vt3iMHLzgZJ71rUVbQQuz".to_string(),
+        "AKo9P7S8FE9NYeAcrtZEpimwQAXJMp8Lrt8p4dMkHkY2".to_string(),
+        "HXyEGGvDDJvXuYkvD7cwYSwrSPKvqn54BY3BpqGVozei".to_string(), // Non existing mint
+      ];
+      mock.add_asset(asset(&ids[0], TOLY));
+      mock.add_asset(asset(&ids[1], TOLY));
+
+      let res = client.get_asset_batch(&GetAssetBatchParams { ids, display_options: None }).await?;
+      assert!(res.len() == 3);
+      assert!(res[2].is_none()); // make sure non-existing mint is deserialized as None
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_asset_by_owner() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let opts = DisplayOptions { show_fungible: true, show_inscription: false };
+      let res = client
+        .get_assets_by_owner(&GetAssetsByOwnerParams {
+          display_options: Some(opts),
+          owner_address: MAD_LAD_OWNER.to_string(),
+          sort_by: None,
+          pagination: Pagination::default(),
+        })
+        .await?;
+      assert!(!res.items.is_empty());
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_asset_by_non_exist_owner() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let owner = String::from("HXyEGGvDDJvXuYkvD7cwYSwrSPKvqn54BY3BpqGVozei");
+      let opts = DisplayOptions { show_fungible: true, show_inscription: false };
+      let res = client
+        .get_assets_by_owner(&GetAssetsByOwnerParams {
+          display_options: Some(opts),
+          owner_address: owner.clone(),
+          sort_by: None,
+          pagination: Pagination::default(),
+        })
+        .await?;
+      assert!(res.items.is_empty());
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn asset_by_authority() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let rando = String::from("2RtGg6fsFiiF1EQzHqbd66AhW7R5bWeQGpTbv2UMkCdW");
+      let res = client
+        .get_assets_by_authority(&GetAssetsByAuthorityParams {
+          authority_address: rando,
+          display_options: Some(DisplayOptions::default()),
+          ..Default::default()
+        })
+        .await?;
+      assert!(!res.items.is_empty());
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_asset_by_creator() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let rando = String::from("5XvhfmRjwXkGp3jHGmaKpqeerNYjkuZZBYLVQYdeVcRv");
+      let res = client
+        .get_assets_by_creator(&GetAssetsByCreatorParams {
+          creator_address: rando,
+          only_verified: true,
+          display_options: Some(DisplayOptions { show_fungible: true, show_inscription: true }),
+          pagination: Pagination { limit: Some(300), ..Default::default() },
+          ..Default::default()
+        })
+        .await?;
+      assert!(!res.items.is_empty());
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn search_asset() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let types: Vec<TokenType> =
+        vec![TokenType::All, TokenType::CompressedNft, TokenType::NonFungible, TokenType::RegularNft];
+      for t in types {
+        let r = client
+          .search_assets(&SearchAssetsParams {
+            owner_address: Some(MAD_LAD_OWNER.to_string()),
+            token_type: Some(t),
+            pagination: Pagination { limit: Some(100), ..Default::default() },
+            display_options: Some(SearchAssetDisplayOptions { show_native_balance: true, ..Default::default() }),
+            ..Default::default()
+          })
+          .await?;
+        assert!(!r.items.is_empty());
+        assert!(r.native_balance.is_some());
+      }
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn search_asset_token_info() -> color_eyre::Result<()> {
+      let (mock, client) = mock().await?;
+      mock.add_asset(GetAssetResponse {
+        token_info: Some(TokenInfo {
+          symbol: String::from("USDC"),
+          balance: 10,
+          price_info: PriceInfo {
+            price_per_token: BigDecimal::from(1),
+            total_price: BigDecimal::from(10),
+            ..Default::default()
+          },
+          ..Default::default()
+        }),
+        ..asset("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", TOLY)
+      });
+      let response: Vec<TokenInfo> = client
+        .search_assets(&SearchAssetsParams {
+          owner_address: Some(TOLY.to_string()),
+          token_type: Some(TokenType::Fungible),
+          pagination: Pagination { limit: Some(100), ..Default::default() },
+          ..Default::default()
+        })
+        .await?
+        .items
+        .into_iter()
+        .filter_map(|i| i.token_info)
+        .collect();
+
+      assert!(!response.is_empty());
+      let usdc = response.into_iter().find(|t| t.symbol == "USDC");
+      assert!(usdc.is_some());
+      let usdc = usdc.unwrap();
+      assert!(usdc.price_info.total_price > BigDecimal::zero());
+      assert!(usdc.price_info.price_per_token > BigDecimal::zero());
+      assert!(usdc.balance > 0);
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn asset_groups() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let rando = String::from("J1S9H3QjnRtBbbuD4HjPV6RpRhwuk4zKbxsnCHuTgh9w");
+      let res = client
+        .get_assets_by_group(&GetAssetsByGroupParams {
+          group_key: "collection".to_string(),
+          group_value: rando,
+          ..Default::default()
+        })
+        .await?;
+      assert!(!res.items.is_empty());
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn asset_proof() -> color_eyre::Result<()> {
+      let (mock, client) = mock().await?;
+      let rando = String::from("Bu1DEKeawy7txbnCEJE4BU3BKLXaNAKCYcHR4XhndGss");
+      mock.add_asset_proof(&rando, GetAssetProofResponse::default());
+      assert!(client.get_asset_proof(&GetAssetProofParams { id: rando }).await?.is_some());
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn asset_proof_batch() -> color_eyre::Result<()> {
+      let (mock, client) = mock().await?;
+      let rando = String::from("Bu1DEKeawy7txbnCEJE4BU3BKLXaNAKCYcHR4XhndGss");
+      mock.add_asset_proof(&rando, GetAssetProofResponse::default());
+      let proofs = client.get_asset_proof_batch(&GetAssetProofBatchParams { ids: vec![rando.clone()] }).await?;
+      assert!(proofs.contains_key(&rando));
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_token_accounts_owner() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let token_accounts = client
+        .get_token_accounts(&GetTokenAccountsParams { owner: Some(MAD_LAD_OWNER.to_string()), ..Default::default() })
+        .await?;
+      assert!(!token_accounts.token_accounts.is_empty());
+      assert!(token_accounts.total > 0);
+      assert_eq!(token_accounts.page, 1);
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_token_accounts_mint() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let token_accounts = client
+        .get_token_accounts(&GetTokenAccountsParams { mint: Some(MAD_LAD.to_string()), ..Default::default() })
+        .await?;
+      assert!(!token_accounts.token_accounts.is_empty());
+      assert!(token_accounts.total > 0);
+      assert_eq!(token_accounts.page, 1);
+      Ok(())
+    }
+
+    #[tokio::test]
+    async fn get_estimate_fee() -> color_eyre::Result<()> {
+      let (_mock, client) = mock().await?;
+      let randos = vec![
+        String::from("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4"),
+        String::from("oreoN2tQbHXVaZsr3pf66A48miqcBXCDJozganhEJgz"),
+      ];
+      let fees = client.get_estimate_priority_fee_levels(Vec::clone(&randos)).await?;
+      assert!(fees.high > 0.0);
+      let fees = client.get_estimate_priority_fee(randos, PriorityLevel::High).await?;
+      assert!(fees > 0.0);
+      Ok(())
+    }
+  }
+
   #[test]
   fn cluster_from_str() -> color_eyre::Result<()> {
     let c = Cluster::from_str("mainnet")?;
@@ -601,6 +1010,24 @@ mod tests {
     let r = Cluster::from_str("test");
     assert!(r.is_err());
 
+    let c = Cluster::from_str("https://my-node.helius-rpc.com/?api-key=secret")?;
+    assert_eq!(
+      Cluster::Custom {
+        rpc: String::from("https://my-node.helius-rpc.com/?api-key=secret"),
+        api: String::from("https://api-mainnet.helius-rpc.com/v0")
+      },
+      c
+    );
+
+    assert_eq!(Cluster::from_str("https://mainnet.helius-rpc.com")?, Cluster::MainnetBeta);
+    assert_eq!(Cluster::from_str("https://mainnet.helius-rpc.com/?api-key=secret")?, Cluster::MainnetBeta);
+    assert_eq!(Cluster::from_str("https://devnet.helius-rpc.com/")?, Cluster::Devnet);
+
+    let c = Cluster::from_str("http://devnet.example.com:8899")?;
+    assert!(matches!(c, Cluster::Custom { api, .. } if api == "https://api-devnet.helius-rpc.com/v0"));
+
+    assert!(Cluster::from_str("ftp://mainnet.example.com").is_err());
+
     Ok(())
   }
 }
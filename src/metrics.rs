@@ -0,0 +1,258 @@
+//! Request metrics in the [Prometheus text format](https://prometheus.io/docs/instrumenting/exposition_formats/).
+//!
+//! Every HTTP request of the REST API, DAS and solana RPC calls is recorded, retries included, with the labels
+//! - `endpoint`: the JSON-RPC method, `batch` for JSON-RPC batches,
+//!   or the REST path with base58 addresses, signatures and UUIDs replaced by `{id}`
+//! - `cluster`: `mainnet-beta`, `devnet` or `custom`
+//! - `error`: the [`HeliusError`] variant, on `helius_request_errors_total` only
+//!
+//! Durations do not include the time spent waiting on a rate limit.
+//!
+//! ```rust
+//! let helius = selene_helius_sdk::HeliusBuilder::new("something").build()?;
+//! // serve this from your /metrics handler
+//! let text = helius.metrics().render();
+//! # Ok::<(), selene_helius_sdk::error::HeliusError>(())
+//! ```
+use crate::error::HeliusError;
+use crate::Cluster;
+use async_trait::async_trait;
+use serde_json::Value;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// Upper bounds of the request duration histogram, in seconds
+pub const DURATION_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Clone, Debug, Default)]
+struct Histogram {
+  buckets: [u64; DURATION_BUCKETS.len()],
+  sum: f64,
+  count: u64,
+}
+
+impl Histogram {
+  fn observe(&mut self, seconds: f64) {
+    if let Some(i) = DURATION_BUCKETS.iter().position(|b| seconds <= *b) {
+      self.buckets[i] += 1;
+    }
+    self.sum += seconds;
+    self.count += 1;
+  }
+}
+
+type Labels = (String, &'static str);
+
+#[derive(Debug, Default)]
+struct Series {
+  requests: BTreeMap<Labels, u64>,
+  errors: BTreeMap<(String, &'static str, &'static str), u64>,
+  durations: BTreeMap<Labels, Histogram>,
+}
+
+/// Counters and histograms of the requests sent by a [`crate::Helius`] client.
+/// A registry can be shared between clients with [`crate::HeliusBuilder::metrics`]
+#[derive(Debug, Default)]
+pub struct Metrics {
+  series: Mutex<Series>,
+}
+
+pub(crate) const fn cluster_label(cluster: &Cluster) -> &'static str {
+  match cluster {
+    Cluster::MainnetBeta => "mainnet-beta",
+    Cluster::Devnet => "devnet",
+    Cluster::Custom { .. } => "custom",
+  }
+}
+
+/// The JSON-RPC method of `body`, or the path of `url` with ids replaced by `{id}`
+pub(crate) fn endpoint(url: &Url, body: Option<&str>) -> String {
+  match body.and_then(|b| serde_json::from_str::<Value>(b).ok()) {
+    Some(Value::Object(req)) if req.get("method").is_some_and(Value::is_string) => {
+      req["method"].as_str().unwrap_or_default().to_string()
+    },
+    Some(Value::Array(_)) => String::from("batch"),
+    _ => url.path().split('/').map(|s| if is_id(s) { "{id}" } else { s }).collect::<Vec<_>>().join("/"),
+  }
+}
+
+/// A base58 address or signature, or a UUID such as a webhook id
+fn is_id(segment: &str) -> bool {
+  let base58 = (32..=88).contains(&segment.len())
+    && segment.bytes().all(|b| b.is_ascii_alphanumeric() && !matches!(b, b'0' | b'O' | b'I' | b'l'));
+  let uuid = segment.split('-').map(str::len).eq([8, 4, 4, 4, 12])
+    && segment.bytes().all(|b| b == b'-' || b.is_ascii_hexdigit());
+  base58 || uuid
+}
+
+fn escape(value: &str) -> String {
+  value.replace('\\', r"\\").replace('"', "\\\"").replace('\n', r"\n")
+}
+
+impl Metrics {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Record one request to `endpoint` which took `elapsed` and failed with `error`, if any
+  pub fn record(&self, endpoint: &str, cluster: &'static str, elapsed: Duration, error: Option<&HeliusError>) {
+    self.observe(endpoint, cluster, elapsed, error.map(HeliusError::variant_name));
+  }
+
+  fn observe(&self, endpoint: &str, cluster: &'static str, elapsed: Duration, error: Option<&'static str>) {
+    let mut series = self.series.lock().unwrap_or_else(PoisonError::into_inner);
+    *series.requests.entry((endpoint.to_string(), cluster)).or_default() += 1;
+    series.durations.entry((endpoint.to_string(), cluster)).or_default().observe(elapsed.as_secs_f64());
+    if let Some(err) = error {
+      *series.errors.entry((endpoint.to_string(), cluster, err)).or_default() += 1;
+    }
+  }
+
+  /// Render every metric in the Prometheus text exposition format
+  pub fn render(&self) -> String {
+    let series = self.series.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut out = String::new();
+    let _ = writeln!(out, "# HELP helius_requests_total HTTP requests sent, retries included");
+    let _ = writeln!(out, "# TYPE helius_requests_total counter");
+    for ((endpoint, cluster), count) in &series.requests {
+      let _ = writeln!(out, "helius_requests_total{{endpoint=\"{}\",cluster=\"{cluster}\"}} {count}", escape(endpoint));
+    }
+    let _ = writeln!(out, "# HELP helius_request_errors_total Failed HTTP requests by error");
+    let _ = writeln!(out, "# TYPE helius_request_errors_total counter");
+    for ((endpoint, cluster, error), count) in &series.errors {
+      let _ = writeln!(
+        out,
+        "helius_request_errors_total{{endpoint=\"{}\",cluster=\"{cluster}\",error=\"{error}\"}} {count}",
+        escape(endpoint)
+      );
+    }
+    let _ = writeln!(out, "# HELP helius_request_duration_seconds HTTP request duration");
+    let _ = writeln!(out, "# TYPE helius_request_duration_seconds histogram");
+    for ((endpoint, cluster), histogram) in &series.durations {
+      let labels = format!("endpoint=\"{}\",cluster=\"{cluster}\"", escape(endpoint));
+      let mut cumulative = 0;
+      for (bound, count) in DURATION_BUCKETS.iter().zip(histogram.buckets) {
+        cumulative += count;
+        let _ = writeln!(out, "helius_request_duration_seconds_bucket{{{labels},le=\"{bound}\"}} {cumulative}");
+      }
+      let _ = writeln!(out, "helius_request_duration_seconds_bucket{{{labels},le=\"+Inf\"}} {}", histogram.count);
+      let _ = writeln!(out, "helius_request_duration_seconds_sum{{{labels}}} {}", histogram.sum);
+      let _ = writeln!(out, "helius_request_duration_seconds_count{{{labels}}} {}", histogram.count);
+    }
+    drop(series);
+    out
+  }
+}
+
+/// [`RpcSender`] which records every call of the solana [`RpcClient`], failures as `SolanaClientError`
+pub(crate) struct MeteredSender {
+  inner: RpcClient,
+  metrics: Arc<Metrics>,
+  cluster: &'static str,
+}
+
+impl MeteredSender {
+  pub fn new(inner: RpcClient, metrics: Arc<Metrics>, cluster: &'static str) -> Self {
+    Self { inner, metrics, cluster }
+  }
+}
+
+#[async_trait]
+impl RpcSender for MeteredSender {
+  async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+    let start = Instant::now();
+    let result = self.inner.send(request, params).await;
+    let error = result.as_ref().err().map(|_| "SolanaClientError");
+    self.metrics.observe(&request.to_string(), self.cluster, start.elapsed(), error);
+    result
+  }
+
+  fn get_transport_stats(&self) -> RpcTransportStats {
+    self.inner.get_transport_stats()
+  }
+
+  fn url(&self) -> String {
+    self.inner.url()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::error::HeliusError;
+  use crate::metrics::{endpoint, Metrics};
+  use crate::HeliusBuilder;
+  use std::time::Duration;
+  use url::Url;
+
+  #[test]
+  fn endpoint_labels() -> color_eyre::Result<()> {
+    let rpc = Url::parse("https://mainnet.helius-rpc.com/?api-key=secret")?;
+    assert_eq!(endpoint(&rpc, Some(r#"{"jsonrpc":"2.0","id":"1","method":"getAsset","params":{}}"#)), "getAsset");
+    assert_eq!(endpoint(&rpc, Some(r#"[{"method":"getAsset"}]"#)), "batch");
+    let url = Url::parse("https://api.helius.xyz/v0/addresses/86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY/names")?;
+    assert_eq!(endpoint(&url, None), "/v0/addresses/{id}/names");
+    let url = Url::parse("https://api.helius.xyz/v0/webhooks/0e8250a1-ceec-4757-ad69-cc62b0d6ed2c")?;
+    assert_eq!(endpoint(&url, None), "/v0/webhooks/{id}");
+    let url = Url::parse("https://api.helius.xyz/v0/transactions?api-key=secret")?;
+    assert_eq!(endpoint(&url, Some(r#"{"transactions":[]}"#)), "/v0/transactions");
+    Ok(())
+  }
+
+  #[test]
+  fn render_text_format() {
+    let metrics = Metrics::new();
+    let limited = HeliusError::TooManyRequests { path: String::new(), retry_after: None };
+    metrics.record("getAsset", "devnet", Duration::from_millis(20), None);
+    metrics.record("getAsset", "devnet", Duration::from_secs(20), Some(&limited));
+    let text = metrics.render();
+    for line in [
+      "# TYPE helius_requests_total counter",
+      r#"helius_requests_total{endpoint="getAsset",cluster="devnet"} 2"#,
+      r#"helius_request_errors_total{endpoint="getAsset",cluster="devnet",error="TooManyRequests"} 1"#,
+      "# TYPE helius_request_duration_seconds histogram",
+      r#"helius_request_duration_seconds_bucket{endpoint="getAsset",cluster="devnet",le="0.01"} 0"#,
+      r#"helius_request_duration_seconds_bucket{endpoint="getAsset",cluster="devnet",le="0.025"} 1"#,
+      r#"helius_request_duration_seconds_bucket{endpoint="getAsset",cluster="devnet",le="10"} 1"#,
+      r#"helius_request_duration_seconds_bucket{endpoint="getAsset",cluster="devnet",le="+Inf"} 2"#,
+      r#"helius_request_duration_seconds_count{endpoint="getAsset",cluster="devnet"} 2"#,
+    ] {
+      assert!(text.lines().any(|l| l == line), "missing {line} in\n{text}");
+    }
+  }
+
+  #[tokio::test]
+  async fn record_requests() -> color_eyre::Result<()> {
+    let mut server = mockito::Server::new_async().await;
+    let ok = server.mock("GET", "/v0/webhooks").match_query(mockito::Matcher::Any).with_body("[]").create_async().await;
+    let helius = HeliusBuilder::new("something").api_url(&format!("{}/v0", server.url())).build()?;
+    helius.get_all_webhooks().await?;
+    ok.assert_async().await;
+    assert!(helius.get_webhook_by_id("0e8250a1-ceec-4757-ad69-cc62b0d6ed2c").await.is_err());
+    drop(server);
+    let text = helius.metrics().render();
+    assert!(text.contains(r#"helius_requests_total{endpoint="/v0/webhooks",cluster="custom"} 1"#));
+    assert!(text.contains(r#"endpoint="/v0/webhooks/{id}",cluster="custom",error="#));
+    assert!(!text.contains("something"));
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn record_rpc_calls() -> color_eyre::Result<()> {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server.mock("POST", "/").with_body(r#"{"jsonrpc":"2.0","id":1,"result":"ok"}"#).create_async().await;
+    let helius = HeliusBuilder::new("something").rpc_url(&server.url()).build()?;
+    helius.connection().get_health().await?;
+    mock.assert_async().await;
+    drop(server);
+    let text = helius.metrics().render();
+    assert!(text.contains(r#"helius_requests_total{endpoint="getHealth",cluster="custom"} 1"#));
+    Ok(())
+  }
+}
@@ -0,0 +1,192 @@
+//! Hooks around every REST API and DAS request.
+//!
+//! Each [`Middleware`] added with [`HeliusBuilder::middleware`](crate::HeliusBuilder::middleware) sees the request
+//! before it is sent and the response, or the transport error, once it is received.
+//! Middleware run in the order they were added, the first one being the outermost.
+//! They run on every attempt when a [`RetryPolicy`](crate::retry::RetryPolicy) retries a request.
+//!
+//! ```rust
+//! use async_trait::async_trait;
+//! use selene_helius_sdk::middleware::{Middleware, Next};
+//! use selene_helius_sdk::transport::{HttpRequest, HttpResponse};
+//! use std::time::Instant;
+//!
+//! struct Timer;
+//!
+//! #[async_trait]
+//! impl Middleware for Timer {
+//!   async fn handle(&self, request: HttpRequest, next: Next<'_>) -> selene_helius_sdk::Result<HttpResponse> {
+//!     let start = Instant::now();
+//!     let response = next.run(request).await;
+//!     println!("took {:?}", start.elapsed());
+//!     response
+//!   }
+//! }
+//!
+//! let helius = selene_helius_sdk::HeliusBuilder::new("something").middleware(Timer).build();
+//! ```
+use crate::secret::without_api_key;
+use crate::transport::{HttpRequest, HttpResponse, Transport};
+use crate::Result;
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::{debug, warn};
+
+#[async_trait]
+pub trait Middleware: Send + Sync {
+  /// Call [`Next::run`] to pass the request on, or return a response without sending it
+  async fn handle(&self, request: HttpRequest, next: Next<'_>) -> Result<HttpResponse>;
+}
+
+/// The rest of the middleware chain, followed by the transport
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+  transport: &'a dyn Transport,
+  middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl Next<'_> {
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError`] if a later middleware or the transport fails
+  pub async fn run(self, request: HttpRequest) -> Result<HttpResponse> {
+    match self.middleware.split_first() {
+      Some((first, rest)) => first.handle(request, Next { transport: self.transport, middleware: rest }).await,
+      None => self.transport.send(request).await,
+    }
+  }
+}
+
+/// A [`Transport`] running a middleware chain in front of another transport
+pub(crate) struct Chain {
+  pub(crate) transport: Arc<dyn Transport>,
+  pub(crate) middleware: Vec<Arc<dyn Middleware>>,
+}
+
+#[async_trait]
+impl Transport for Chain {
+  async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+    Next { transport: &*self.transport, middleware: &self.middleware }.run(request).await
+  }
+}
+
+/// Add headers to every request, e.g. the credentials of a proxy in front of Helius
+#[derive(Clone, Debug, Default)]
+pub struct Headers(pub HeaderMap);
+
+#[async_trait]
+impl Middleware for Headers {
+  async fn handle(&self, mut request: HttpRequest, next: Next<'_>) -> Result<HttpResponse> {
+    request.headers.extend(self.0.clone());
+    next.run(request).await
+  }
+}
+
+/// Log every request with its status and duration, the `api-key` query parameter is left out of the url
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestLog;
+
+#[async_trait]
+impl Middleware for RequestLog {
+  async fn handle(&self, request: HttpRequest, next: Next<'_>) -> Result<HttpResponse> {
+    let method = request.method.clone();
+    let url = without_api_key(&request.url);
+    let start = Instant::now();
+    let response = next.run(request).await;
+    let elapsed_ms = start.elapsed().as_millis();
+    match &response {
+      Ok(res) => debug!(status = res.status.as_u16(), elapsed_ms, "{method} {url}"),
+      Err(err) => warn!(error = %err, elapsed_ms, "{method} {url}"),
+    }
+    response
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::middleware::{Headers, Middleware, Next, RequestLog};
+  use crate::transport::{HttpRequest, HttpResponse};
+  use crate::HeliusBuilder;
+  use async_trait::async_trait;
+  use reqwest::header::{HeaderMap, HeaderValue};
+  use reqwest::StatusCode;
+  use std::sync::{Arc, Mutex, PoisonError};
+
+  type Log = Arc<Mutex<Vec<String>>>;
+
+  struct Observer {
+    name: &'static str,
+    log: Log,
+  }
+
+  #[async_trait]
+  impl Middleware for Observer {
+    async fn handle(&self, request: HttpRequest, next: Next<'_>) -> crate::Result<HttpResponse> {
+      let before = format!("{} {} {}", self.name, request.method, request.url.path());
+      self.log.lock().unwrap_or_else(PoisonError::into_inner).push(before);
+      let response = next.run(request).await;
+      let after = response
+        .as_ref()
+        .map_or_else(|_| format!("{} error", self.name), |res| format!("{} {}", self.name, res.status.as_u16()));
+      self.log.lock().unwrap_or_else(PoisonError::into_inner).push(after);
+      response
+    }
+  }
+
+  struct Canned;
+
+  #[async_trait]
+  impl Middleware for Canned {
+    async fn handle(&self, _request: HttpRequest, _next: Next<'_>) -> crate::Result<HttpResponse> {
+      Ok(HttpResponse { status: StatusCode::OK, headers: HeaderMap::new(), body: String::from("[]") })
+    }
+  }
+
+  fn entries(log: &Log) -> Vec<String> {
+    log.lock().unwrap_or_else(PoisonError::into_inner).clone()
+  }
+
+  #[tokio::test]
+  async fn chain_order_and_headers() -> color_eyre::Result<()> {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+      .mock("GET", "/v0/webhooks")
+      .match_query(mockito::Matcher::Any)
+      .match_header("x-proxy-key", "proxy")
+      .with_body("[]")
+      .create_async()
+      .await;
+    let log = Log::default();
+    let mut headers = HeaderMap::new();
+    headers.insert("x-proxy-key", HeaderValue::from_static("proxy"));
+    let helius = HeliusBuilder::new("something")
+      .api_url(&format!("{}/v0", server.url()))
+      .middleware(Observer { name: "outer", log: log.clone() })
+      .middleware(Headers(headers))
+      .middleware(RequestLog)
+      .middleware(Observer { name: "inner", log: log.clone() })
+      .build()?;
+    assert!(helius.get_all_webhooks().await?.is_empty());
+    mock.assert_async().await;
+    drop(server);
+    assert_eq!(entries(&log), ["outer GET /v0/webhooks", "inner GET /v0/webhooks", "inner 200", "outer 200"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn short_circuit_and_errors() -> color_eyre::Result<()> {
+    let helius = HeliusBuilder::new("something").api_url("http://127.0.0.1:1/v0").middleware(Canned).build()?;
+    assert!(helius.get_all_webhooks().await?.is_empty());
+
+    let log = Log::default();
+    let helius = HeliusBuilder::new("something")
+      .api_url("http://127.0.0.1:1/v0")
+      .middleware(Observer { name: "outer", log: log.clone() })
+      .build()?;
+    assert!(helius.get_all_webhooks().await.is_err());
+    assert_eq!(entries(&log), ["outer GET /v0/webhooks", "outer error"]);
+    Ok(())
+  }
+}
@@ -0,0 +1,178 @@
+use async_trait::async_trait;
+use solana_client::client_error::Result as ClientResult;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Request budget of a single endpoint
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quota {
+  /// Sustained requests per second
+  pub per_second: u32,
+  /// Requests which may be sent at once before throttling kicks in
+  pub burst: u32,
+}
+
+impl Quota {
+  pub const fn per_second(per_second: u32) -> Self {
+    Self { per_second, burst: per_second }
+  }
+
+  pub(crate) fn bucket(self) -> Arc<TokenBucket> {
+    Arc::new(TokenBucket::new(self))
+  }
+}
+
+/// [Helius plans](https://www.helius.dev/pricing)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Plan {
+  Free,
+  Developer,
+  Business,
+  Professional,
+}
+
+/// Client side rate limits, `None` means unlimited.
+///
+/// # Example
+///```rust
+/// use selene_helius_sdk::rate_limit::{Plan, RateLimits};
+/// let helius = selene_helius_sdk::HeliusBuilder::new("something")
+///       .rate_limits(RateLimits::from(Plan::Developer))
+///       .build()
+///       .expect("failed to create client");
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RateLimits {
+  /// REST API, such as webhooks and enhanced transactions
+  pub api: Option<Quota>,
+  /// DAS API
+  pub das: Option<Quota>,
+  /// Solana RPC calls made with [`crate::Helius::connection`]
+  pub rpc: Option<Quota>,
+}
+
+impl From<Plan> for RateLimits {
+  fn from(plan: Plan) -> Self {
+    let (api, das, rpc) = match plan {
+      Plan::Free => (2, 2, 10),
+      Plan::Developer => (10, 10, 50),
+      Plan::Business => (50, 50, 200),
+      Plan::Professional => (100, 100, 500),
+    };
+    Self { api: Some(Quota::per_second(api)), das: Some(Quota::per_second(das)), rpc: Some(Quota::per_second(rpc)) }
+  }
+}
+
+/// Token bucket, callers wait until a token is available
+#[derive(Debug)]
+pub struct TokenBucket {
+  quota: Quota,
+  state: Mutex<BucketState>,
+}
+
+#[derive(Debug)]
+struct BucketState {
+  tokens: f64,
+  updated: Instant,
+}
+
+impl TokenBucket {
+  pub fn new(quota: Quota) -> Self {
+    let quota = Quota { per_second: quota.per_second.max(1), burst: quota.burst.max(1) };
+    Self { quota, state: Mutex::new(BucketState { tokens: f64::from(quota.burst), updated: Instant::now() }) }
+  }
+
+  /// Take a token, waiting for the bucket to refill if it is empty
+  pub async fn acquire(&self) {
+    let wait = self.reserve();
+    if !wait.is_zero() {
+      tokio::time::sleep(wait).await;
+    }
+  }
+
+  /// Take a token and return how long the caller has to wait before using it.
+  /// Tokens may go negative, so concurrent callers queue up behind each other.
+  fn reserve(&self) -> Duration {
+    let mut state = self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    let now = Instant::now();
+    let rate = f64::from(self.quota.per_second);
+    let elapsed = now.duration_since(state.updated).as_secs_f64();
+    state.tokens = elapsed.mul_add(rate, state.tokens).min(f64::from(self.quota.burst));
+    state.updated = now;
+    state.tokens -= 1.0;
+    if state.tokens >= 0.0 {
+      Duration::ZERO
+    } else {
+      Duration::from_secs_f64(-state.tokens / rate)
+    }
+  }
+}
+
+/// [`RpcSender`] which waits on a [`TokenBucket`] before every call
+pub(crate) struct RateLimitedSender {
+  inner: RpcClient,
+  bucket: Arc<TokenBucket>,
+}
+
+impl RateLimitedSender {
+  pub fn new(inner: RpcClient, bucket: Arc<TokenBucket>) -> Self {
+    Self { inner, bucket }
+  }
+}
+
+#[async_trait]
+impl RpcSender for RateLimitedSender {
+  async fn send(&self, request: RpcRequest, params: serde_json::Value) -> ClientResult<serde_json::Value> {
+    self.bucket.acquire().await;
+    self.inner.send(request, params).await
+  }
+
+  fn get_transport_stats(&self) -> RpcTransportStats {
+    self.inner.get_transport_stats()
+  }
+
+  fn url(&self) -> String {
+    self.inner.url()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::rate_limit::{Plan, Quota, RateLimits, TokenBucket};
+  use std::time::Duration;
+  use tokio::time::Instant;
+
+  #[test]
+  fn bucket_reserve() {
+    let bucket = TokenBucket::new(Quota { per_second: 10, burst: 2 });
+    assert_eq!(bucket.reserve(), Duration::ZERO);
+    assert_eq!(bucket.reserve(), Duration::ZERO);
+    let wait = bucket.reserve();
+    assert!(wait > Duration::from_millis(50) && wait <= Duration::from_millis(100));
+    let wait = bucket.reserve();
+    assert!(wait > Duration::from_millis(150) && wait <= Duration::from_millis(200));
+  }
+
+  #[tokio::test]
+  async fn bucket_acquire_waits() {
+    let bucket = TokenBucket::new(Quota { per_second: 20, burst: 1 });
+    let start = Instant::now();
+    for _ in 0..3 {
+      bucket.acquire().await;
+    }
+    assert!(start.elapsed() >= Duration::from_millis(90));
+  }
+
+  #[test]
+  fn plans() {
+    let free = RateLimits::from(Plan::Free);
+    assert_eq!(free.das, Some(Quota { per_second: 2, burst: 2 }));
+    let pro = RateLimits::from(Plan::Professional);
+    assert_eq!(pro.rpc, Some(Quota { per_second: 500, burst: 500 }));
+    assert_eq!(RateLimits::default().api, None);
+  }
+}
@@ -1,59 +1,146 @@
 use crate::api::types::RpcError;
 use crate::error::HeliusError;
+#[cfg(feature = "metrics")]
+use crate::metrics::{self, Metrics};
+use crate::rate_limit::TokenBucket;
+use crate::retry::{retry_after, RetryPolicy};
+use crate::secret::ApiKey;
+use crate::transport::{HttpRequest, Transport};
 use crate::Result;
-use reqwest::{Client, Method};
+use reqwest::Method;
 use reqwest::{StatusCode, Url};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::fmt::Debug;
-use tracing::debug;
+use std::fmt::{Debug, Write};
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+/// Longest response body kept in an error, longer bodies are truncated
+const MAX_ERROR_TEXT: usize = 1024;
+
+fn truncate(mut text: String) -> String {
+  if text.len() > MAX_ERROR_TEXT {
+    let mut end = MAX_ERROR_TEXT;
+    while !text.is_char_boundary(end) {
+      end -= 1;
+    }
+    let len = text.len();
+    text.truncate(end);
+    let _ = write!(text, "... ({} bytes truncated)", len - end);
+  }
+  text
+}
+
+/// Endpoint specific mapping of an unsuccessful response, checked before the generic status handling
+pub type StatusHook = fn(StatusCode, &str) -> Option<HeliusError>;
 
 #[derive(Clone)]
 pub struct RequestHandler {
-  pub http_client: Client,
+  pub transport: Arc<dyn Transport>,
+  pub retry_policy: RetryPolicy,
+  pub limiter: Option<Arc<TokenBucket>>,
+  pub api_key: Option<ApiKey>,
+  #[cfg(feature = "metrics")]
+  pub metrics: Option<(Arc<Metrics>, &'static str)>,
 }
 
 impl RequestHandler {
-  pub const fn new(client: Client) -> Self {
-    Self { http_client: client }
+  pub fn new(transport: Arc<dyn Transport>, retry_policy: RetryPolicy, limiter: Option<Arc<TokenBucket>>) -> Self {
+    Self {
+      transport,
+      retry_policy,
+      limiter,
+      api_key: None,
+      #[cfg(feature = "metrics")]
+      metrics: None,
+    }
+  }
+
+  /// Remove `api_key` from the errors returned by this handler
+  #[must_use]
+  pub fn with_api_key(mut self, api_key: ApiKey) -> Self {
+    self.api_key = Some(api_key);
+    self
   }
 
-  #[allow(clippy::option_if_let_else)]
-  async fn send<R, T>(&self, method: Method, url: Url, body: Option<&R>) -> Result<T>
+  /// Record every request in `metrics` with the `cluster` label
+  #[cfg(feature = "metrics")]
+  #[must_use]
+  pub fn with_metrics(mut self, metrics: Arc<Metrics>, cluster: &'static str) -> Self {
+    self.metrics = Some((metrics, cluster));
+    self
+  }
+
+  async fn send<R, T>(&self, method: Method, url: Url, body: Option<&R>, on_status: Option<StatusHook>) -> Result<T>
   where
     R: Serialize + ?Sized + Debug + Send + Sync,
     T: DeserializeOwned + Default,
+  {
+    let body =
+      body.map(serde_json::to_string).transpose().map_err(|err| HeliusError::SerdeJson { err, text: String::new() })?;
+    #[cfg(feature = "metrics")]
+    let endpoint = self.metrics.as_ref().map(|_| metrics::endpoint(&url, body.as_deref()));
+    let idempotent = matches!(method, Method::GET | Method::PUT | Method::DELETE);
+    let mut attempt = 1;
+    loop {
+      if let Some(limiter) = &self.limiter {
+        limiter.acquire().await;
+      }
+      // started after the rate limit wait, which is not part of the request duration
+      #[cfg(feature = "metrics")]
+      let start = std::time::Instant::now();
+      let result =
+        self.send_once(method.clone(), url.clone(), body.clone(), on_status).await.map_err(|err| match &self.api_key {
+          Some(key) => err.redact(key),
+          None => err,
+        });
+      #[cfg(feature = "metrics")]
+      if let (Some((metrics, cluster)), Some(endpoint)) = (&self.metrics, &endpoint) {
+        metrics.record(endpoint, cluster, start.elapsed(), result.as_ref().err());
+      }
+      match result {
+        Ok(res) => return Ok(res),
+        Err(err) => match self.retry_policy.delay(attempt, &err, idempotent) {
+          None => return Err(err),
+          Some(delay) => {
+            warn!(attempt, delay_ms = delay.as_millis(), error = %err, "retrying request {method} {}", url.path());
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+          },
+        },
+      }
+    }
+  }
+
+  async fn send_once<T>(
+    &self,
+    method: Method,
+    url: Url,
+    body: Option<String>,
+    on_status: Option<StatusHook>,
+  ) -> Result<T>
+  where
+    T: DeserializeOwned + Default,
   {
     let path = String::from(url.path());
-    #[cfg(not(debug))]
+    #[cfg(not(feature = "debug"))]
     debug!("sending request {method} {path}");
 
-    #[cfg(debug)]
-    match body {
+    #[cfg(feature = "debug")]
+    match &body {
       None => debug!("sending request {method} {path}"),
-      Some(b) => {
-        if let Ok(s) = serde_json::to_string(b) {
-          debug!("sending request {method} {path} {:#?}", s);
-        }
-      },
+      Some(s) => debug!("sending request {method} {path} {:#?}", s),
     }
 
-    let mut req = match method {
-      Method::GET => self.http_client.get(url),
-      Method::POST => self.http_client.post(url),
-      Method::DELETE => self.http_client.delete(url),
-      Method::PATCH => self.http_client.patch(url),
-      Method::PUT => self.http_client.put(url),
-      _ => todo!(),
-    };
-
-    if let Some(b) = body {
-      req = req.json(b);
+    let resp = self.transport.send(HttpRequest::new(method, url, body)).await?;
+    let retry_after = retry_after(&resp.headers);
+    if let Some(err) = on_status.filter(|_| !resp.status.is_success()).and_then(|hook| hook(resp.status, &resp.body)) {
+      return Err(err);
     }
-
-    let resp = req.send().await?;
-    let status_code = resp.status();
-    let text = Self::handle_status(path, status_code, resp.text().await?)?;
+    let text = match Self::handle_status(path, resp.status, resp.body) {
+      Err(HeliusError::TooManyRequests { path, .. }) => return Err(HeliusError::TooManyRequests { path, retry_after }),
+      result => result?,
+    };
     if text.is_empty() {
       return Ok(T::default());
     }
@@ -61,19 +148,21 @@ impl RequestHandler {
       Ok(deserialized) => Ok(deserialized),
       Err(err) => match serde_json::from_str::<RpcError>(&text) {
         Ok(rpc_error) => Err(HeliusError::RpcError { code: rpc_error.error.code, message: rpc_error.error.message }),
-        Err(_) => Err(HeliusError::SerdeJson { err, text }),
+        Err(_) => Err(HeliusError::SerdeJson { err, text: truncate(text) }),
       },
     }
   }
 
   fn handle_status(path: String, status: StatusCode, text: String) -> Result<String> {
-    //TODO trim text, as it can be huge
+    if matches!(status, StatusCode::OK | StatusCode::ACCEPTED | StatusCode::CREATED) {
+      return Ok(text);
+    }
+    let text = truncate(text);
     match status {
-      StatusCode::OK | StatusCode::ACCEPTED | StatusCode::CREATED => Ok(text),
       StatusCode::NOT_FOUND => Err(HeliusError::NotFound { path }),
       StatusCode::BAD_REQUEST => Err(HeliusError::BadRequest { path, text }),
       StatusCode::UNAUTHORIZED => Err(HeliusError::Unauthorized { path, text }),
-      StatusCode::TOO_MANY_REQUESTS => Err(HeliusError::TooManyRequests { path }),
+      StatusCode::TOO_MANY_REQUESTS => Err(HeliusError::TooManyRequests { path, retry_after: None }),
       StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT | StatusCode::INTERNAL_SERVER_ERROR => {
         Err(HeliusError::InternalError { code: status, text })
       },
@@ -85,7 +174,15 @@ impl RequestHandler {
   where
     T: DeserializeOwned + Default,
   {
-    self.send(Method::GET, url, None as Option<&str>).await
+    self.send(Method::GET, url, None as Option<&str>, None).await
+  }
+
+  /// Like [`Self::get`], with `on_status` given the first look at unsuccessful responses
+  pub async fn get_with<T>(&self, url: Url, on_status: StatusHook) -> Result<T>
+  where
+    T: DeserializeOwned + Default,
+  {
+    self.send(Method::GET, url, None as Option<&str>, Some(on_status)).await
   }
 
   pub async fn post<R, T>(&self, url: Url, body: &R) -> Result<T>
@@ -93,7 +190,7 @@ impl RequestHandler {
     R: Serialize + ?Sized + Debug + Send + Sync,
     T: DeserializeOwned + Default,
   {
-    self.send(Method::POST, url, Some(body)).await
+    self.send(Method::POST, url, Some(body), None).await
   }
 
   pub async fn put<R, T>(&self, url: Url, body: &R) -> Result<T>
@@ -101,11 +198,11 @@ impl RequestHandler {
     R: Serialize + ?Sized + Debug + Sync + Send,
     T: DeserializeOwned + Default,
   {
-    self.send(Method::PUT, url, Some(body)).await
+    self.send(Method::PUT, url, Some(body), None).await
   }
 
   pub async fn delete(&self, url: Url) -> Result<()> {
-    self.send(Method::DELETE, url, None as Option<&str>).await
+    self.send(Method::DELETE, url, None as Option<&str>, None).await
   }
 }
 
@@ -113,8 +210,59 @@ impl RequestHandler {
 mod tests {
   use crate::error::HeliusError;
   use crate::request_handler::RequestHandler;
+  use crate::retry::RetryPolicy;
+  use crate::transport::ReqwestTransport;
   use color_eyre::eyre::format_err;
-  use reqwest::StatusCode;
+  use reqwest::{StatusCode, Url};
+  use std::sync::Arc;
+  use std::time::Duration;
+
+  async fn server() -> color_eyre::Result<(mockito::ServerGuard, Url)> {
+    let server = mockito::Server::new_async().await;
+    let url = Url::parse(&server.url())?;
+    Ok((server, url))
+  }
+
+  #[tokio::test]
+  async fn retry_too_many_requests() -> color_eyre::Result<()> {
+    let (mut server, url) = server().await?;
+    let limited =
+      server.mock("GET", "/").with_status(429).with_header("retry-after", "0").expect(2).create_async().await;
+    let ok = server.mock("GET", "/").with_body(r#""done""#).expect(1).create_async().await;
+    let policy = RetryPolicy { base_delay: Duration::from_secs(30), ..Default::default() };
+    let handler = RequestHandler::new(Arc::new(ReqwestTransport::new(reqwest::Client::new())), policy, None);
+    let res: String = handler.get(url.clone()).await?;
+    assert_eq!(res, "done");
+    limited.assert_async().await;
+    ok.assert_async().await;
+
+    let limited = server.mock("GET", "/").with_status(429).with_header("retry-after", "0").create_async().await;
+    let handler =
+      RequestHandler::new(Arc::new(ReqwestTransport::new(reqwest::Client::new())), RetryPolicy::none(), None);
+    let res: crate::Result<String> = handler.get(url).await;
+    match res {
+      Err(HeliusError::TooManyRequests { retry_after, .. }) => assert_eq!(retry_after, Some(Duration::ZERO)),
+      _ => return Err(format_err!("error should be TOO_MANY_REQUESTS ")),
+    }
+    limited.assert_async().await;
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn retry_after_longer_than_max_delay() -> color_eyre::Result<()> {
+    let (mut server, url) = server().await?;
+    let limited =
+      server.mock("GET", "/").with_status(429).with_header("retry-after", "30").expect(1).create_async().await;
+    let policy = RetryPolicy { max_delay: Duration::from_secs(10), ..Default::default() };
+    let handler = RequestHandler::new(Arc::new(ReqwestTransport::new(reqwest::Client::new())), policy, None);
+    let res: crate::Result<String> = handler.get(url).await;
+    match res {
+      Err(HeliusError::TooManyRequests { retry_after, .. }) => assert_eq!(retry_after, Some(Duration::from_secs(30))),
+      _ => return Err(format_err!("error should be TOO_MANY_REQUESTS ")),
+    }
+    limited.assert_async().await;
+    Ok(())
+  }
 
   #[test]
   fn handle_status_text() -> color_eyre::Result<()> {
@@ -134,50 +282,77 @@ mod tests {
     match result {
       Err(HeliusError::NotFound { .. }) => {},
       _ => return Err(format_err!("error should be NOT_FOUND ")),
-    };
+    }
 
     let result = RequestHandler::handle_status(path.clone(), StatusCode::SERVICE_UNAVAILABLE, body.clone());
     assert!(result.is_err());
     match result {
       Err(HeliusError::InternalError { .. }) => {},
       _ => return Err(format_err!("error should be INTERNAL_ERROR ")),
-    };
+    }
 
     let result = RequestHandler::handle_status(path.clone(), StatusCode::INTERNAL_SERVER_ERROR, body.clone());
     assert!(result.is_err());
     match result {
       Err(HeliusError::InternalError { .. }) => {},
       _ => return Err(format_err!("error should be INTERNAL_ERROR ")),
-    };
+    }
 
     let result = RequestHandler::handle_status(path.clone(), StatusCode::BAD_REQUEST, body.clone());
     assert!(result.is_err());
     match result {
       Err(HeliusError::BadRequest { .. }) => {},
       _ => return Err(format_err!("error should be BadRequest ")),
-    };
+    }
 
     let result = RequestHandler::handle_status(path.clone(), StatusCode::UNAUTHORIZED, body.clone());
     assert!(result.is_err());
     match result {
       Err(HeliusError::Unauthorized { .. }) => {},
       _ => return Err(format_err!("error should be UNAUTHORIZED ")),
-    };
+    }
 
     let result = RequestHandler::handle_status(path.clone(), StatusCode::TOO_MANY_REQUESTS, body.clone());
     assert!(result.is_err());
     match result {
       Err(HeliusError::TooManyRequests { .. }) => {},
       _ => return Err(format_err!("error should be TOO_MANY_REQUESTS ")),
-    };
+    }
 
     let result = RequestHandler::handle_status(path, StatusCode::IM_A_TEAPOT, body);
     assert!(result.is_err());
     match result {
       Err(HeliusError::Unknown { .. }) => {},
       _ => return Err(format_err!("error should be UNKNOWN")),
-    };
+    }
 
     Ok(())
   }
+
+  #[test]
+  fn handle_status_ignores_history_gap() -> color_eyre::Result<()> {
+    let text = String::from(
+      r#"{"error":"Failed to find events within the search period. To continue search, query the API again with the `before` parameter set to 5wHu1qwD7q5ifaN5nwdcDqNFo53GJqa7nLp2BeeEpcHCusb4GzARz4GjgzsEHMkBMgCJMGa6GSQ7JQdpwPW7NQn1."}"#,
+    );
+    match RequestHandler::handle_status(String::from("/"), StatusCode::NOT_FOUND, text) {
+      Err(HeliusError::NotFound { .. }) => {},
+      _ => return Err(format_err!("error should be NotFound")),
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn handle_status_truncates_errors() -> color_eyre::Result<()> {
+    let body = "é".repeat(1000);
+    let path = String::from("/");
+    assert_eq!(RequestHandler::handle_status(path.clone(), StatusCode::OK, body.clone())?, body);
+    match RequestHandler::handle_status(path, StatusCode::BAD_REQUEST, body) {
+      Err(HeliusError::BadRequest { text, .. }) => {
+        assert!(text.starts_with(&"é".repeat(512)));
+        assert!(text.ends_with("... (976 bytes truncated)"));
+      },
+      _ => return Err(format_err!("error should be BadRequest")),
+    }
+    Ok(())
+  }
 }
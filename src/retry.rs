@@ -0,0 +1,184 @@
+use crate::error::{ErrorKind, HeliusError};
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::time::Duration;
+
+/// Classes of [`HeliusError`] that a [`RetryPolicy`] may retry, matched on [`HeliusError::kind`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RetryOn {
+  /// Errors of kind [`ErrorKind::RateLimited`] (HTTP 429)
+  TooManyRequests,
+  /// Errors of kind [`ErrorKind::Server`] (HTTP 5xx and JSON-RPC server errors)
+  InternalError,
+  /// Errors of kind [`ErrorKind::Transport`].
+  /// Requests which are not idempotent are only retried when the connection failed,
+  /// a POST may have been processed before it timed out
+  Transport,
+}
+
+impl RetryOn {
+  /// Every class, the errors [`HeliusError::is_retryable`] accepts
+  pub const ALL: [Self; 3] = [Self::TooManyRequests, Self::InternalError, Self::Transport];
+
+  /// `idempotent` is true for GET, PUT and DELETE requests
+  pub fn matches(self, err: &HeliusError, idempotent: bool) -> bool {
+    match (self, err.kind()) {
+      (Self::TooManyRequests, ErrorKind::RateLimited) | (Self::InternalError, ErrorKind::Server) => true,
+      (Self::Transport, ErrorKind::Transport) => {
+        idempotent || matches!(err, HeliusError::ReqwestError(e) if e.is_connect())
+      },
+      _ => false,
+    }
+  }
+}
+
+/// Retry failed requests with exponential backoff
+///
+/// # Example
+///```rust
+/// use selene_helius_sdk::retry::{RetryOn, RetryPolicy};
+/// use std::time::Duration;
+/// let helius = selene_helius_sdk::HeliusBuilder::new("something")
+///       .retry_policy(RetryPolicy {
+///         max_attempts: 5,
+///         base_delay: Duration::from_millis(250),
+///         retry_on: vec![RetryOn::TooManyRequests],
+///         ..Default::default()
+///       })
+///       .build()
+///       .expect("failed to create client");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+  /// Total number of attempts, including the first one
+  pub max_attempts: u32,
+  /// Delay before the first retry, doubled on every following retry
+  pub base_delay: Duration,
+  /// Upper bound for the backoff delay
+  pub max_delay: Duration,
+  /// Randomize each delay between half and the full backoff delay
+  pub jitter: bool,
+  /// Errors which are retried
+  pub retry_on: Vec<RetryOn>,
+}
+
+impl Default for RetryPolicy {
+  fn default() -> Self {
+    Self {
+      max_attempts: 3,
+      base_delay: Duration::from_millis(500),
+      max_delay: Duration::from_secs(10),
+      jitter: true,
+      retry_on: RetryOn::ALL.to_vec(),
+    }
+  }
+}
+
+impl RetryPolicy {
+  /// Policy which never retries
+  pub fn none() -> Self {
+    Self { max_attempts: 1, retry_on: Vec::new(), ..Default::default() }
+  }
+
+  pub fn is_retryable(&self, err: &HeliusError, idempotent: bool) -> bool {
+    self.retry_on.iter().any(|r| r.matches(err, idempotent))
+  }
+
+  /// Delay to wait after `attempt` (starting at 1) of a request failed with `err`,
+  /// or `None` if the request should not be retried.
+  /// A `Retry-After` from a 429 response is waited in full instead of the backoff delay,
+  /// the request is not retried if it is longer than `max_delay`.
+  pub fn delay(&self, attempt: u32, err: &HeliusError, idempotent: bool) -> Option<Duration> {
+    if attempt >= self.max_attempts || !self.is_retryable(err, idempotent) {
+      return None;
+    }
+    if let Some(retry_after) = err.retry_after() {
+      return (retry_after <= self.max_delay).then_some(retry_after);
+    }
+    Some(self.backoff(attempt))
+  }
+
+  fn backoff(&self, attempt: u32) -> Duration {
+    let factor = 2_u32.saturating_pow(attempt.saturating_sub(1));
+    let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+    if !self.jitter || delay.is_zero() {
+      return delay;
+    }
+    let half = delay / 2;
+    half + rand::thread_rng().gen_range(Duration::ZERO..=half)
+  }
+}
+
+/// Parse the `Retry-After` header, only the delay-seconds form is supported
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+  headers.get(RETRY_AFTER)?.to_str().ok()?.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::error::HeliusError;
+  use crate::retry::{retry_after, RetryOn, RetryPolicy};
+  use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+  use reqwest::StatusCode;
+  use std::time::Duration;
+
+  fn too_many(retry_after: Option<Duration>) -> HeliusError {
+    HeliusError::TooManyRequests { path: String::from("/"), retry_after }
+  }
+
+  #[test]
+  fn backoff_is_exponential_and_capped() {
+    let policy = RetryPolicy {
+      max_attempts: 10,
+      base_delay: Duration::from_millis(100),
+      max_delay: Duration::from_millis(500),
+      jitter: false,
+      ..Default::default()
+    };
+    let err = too_many(None);
+    assert_eq!(policy.delay(1, &err, true), Some(Duration::from_millis(100)));
+    assert_eq!(policy.delay(2, &err, true), Some(Duration::from_millis(200)));
+    assert_eq!(policy.delay(3, &err, true), Some(Duration::from_millis(400)));
+    assert_eq!(policy.delay(4, &err, true), Some(Duration::from_millis(500)));
+    assert_eq!(policy.delay(10, &err, true), None);
+  }
+
+  #[test]
+  fn backoff_jitter_range() {
+    let policy = RetryPolicy { base_delay: Duration::from_millis(100), ..Default::default() };
+    for _ in 0..100 {
+      let d = policy.delay(1, &too_many(None), true).unwrap_or_default();
+      assert!(d >= Duration::from_millis(50) && d <= Duration::from_millis(100));
+    }
+  }
+
+  #[test]
+  fn retryable_errors() {
+    let policy = RetryPolicy { retry_on: vec![RetryOn::InternalError], ..Default::default() };
+    assert!(
+      policy.is_retryable(&HeliusError::InternalError { code: StatusCode::BAD_GATEWAY, text: String::new() }, false)
+    );
+    assert!(!policy.is_retryable(&too_many(None), true));
+    assert!(!policy.is_retryable(&HeliusError::NotFound { path: String::from("/") }, true));
+    let rpc = HeliusError::RpcError { code: -32603, message: String::from("Internal error") };
+    assert!(policy.is_retryable(&rpc, false));
+    for err in [too_many(None), rpc, HeliusError::BadRequest { path: String::new(), text: String::new() }] {
+      assert_eq!(RetryPolicy::default().is_retryable(&err, true), err.is_retryable());
+    }
+    assert_eq!(RetryPolicy::none().delay(1, &too_many(None), true), None);
+  }
+
+  #[test]
+  fn honor_retry_after() {
+    let policy = RetryPolicy::default();
+    assert_eq!(policy.delay(1, &too_many(Some(Duration::from_secs(7))), false), Some(Duration::from_secs(7)));
+    assert_eq!(policy.delay(1, &too_many(policy.max_delay.checked_add(Duration::from_secs(1))), false), None);
+    assert_eq!(policy.delay(1, &too_many(Some(policy.max_delay)), false), Some(policy.max_delay));
+    let mut headers = HeaderMap::new();
+    assert_eq!(retry_after(&headers), None);
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("3"));
+    assert_eq!(retry_after(&headers), Some(Duration::from_secs(3)));
+    headers.insert(RETRY_AFTER, HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"));
+    assert_eq!(retry_after(&headers), None);
+  }
+}
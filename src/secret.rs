@@ -0,0 +1,116 @@
+//! Keeps the Helius API key out of logs and error messages.
+//!
+//! The key is only readable through [`ApiKey::expose_secret`], its [`Debug`] output is redacted and it has no
+//! [`std::fmt::Display`] impl. Errors returned by [`crate::Helius`] have the key removed from their messages,
+//! including the urls of [`reqwest::Error`] and [`solana_client::client_error::ClientError`].
+use std::fmt::{Debug, Formatter};
+use url::Url;
+
+const REDACTED: &str = "***";
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct ApiKey(String);
+
+impl ApiKey {
+  pub fn new(key: impl Into<String>) -> Self {
+    Self(key.into())
+  }
+
+  /// The key itself, do not log it
+  pub fn expose_secret(&self) -> &str {
+    &self.0
+  }
+
+  /// `text` with every occurrence of the key replaced by `***`
+  pub fn redact(&self, text: &str) -> String {
+    if self.0.is_empty() {
+      text.to_string()
+    } else {
+      text.replace(&self.0, REDACTED)
+    }
+  }
+
+  pub(crate) fn redact_url(&self, url: &mut Url) {
+    *url = without_api_key(url);
+    if !self.0.is_empty() && url.as_str().contains(&self.0) {
+      url.set_path(REDACTED);
+    }
+  }
+}
+
+impl Debug for ApiKey {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(f, "ApiKey({REDACTED})")
+  }
+}
+
+impl From<&str> for ApiKey {
+  fn from(key: &str) -> Self {
+    Self::new(key)
+  }
+}
+
+impl From<String> for ApiKey {
+  fn from(key: String) -> Self {
+    Self::new(key)
+  }
+}
+
+/// `url` without the `api-key` query parameter
+pub(crate) fn without_api_key(url: &Url) -> Url {
+  let mut stripped = url.clone();
+  let query: Vec<(String, String)> =
+    url.query_pairs().filter(|(k, _)| k != "api-key").map(|(k, v)| (k.into_owned(), v.into_owned())).collect();
+  if query.is_empty() {
+    stripped.set_query(None);
+  } else {
+    stripped.query_pairs_mut().clear().extend_pairs(query);
+  }
+  stripped
+}
+
+/// `text` with the value of every `api-key=` parameter replaced by `***`, for urls embedded in error messages
+pub(crate) fn without_api_key_param(text: &str) -> String {
+  const PARAM: &str = "api-key=";
+  let mut out = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find(PARAM) {
+    let (head, tail) = rest.split_at(start + PARAM.len());
+    out.push_str(head);
+    out.push_str(REDACTED);
+    rest = tail
+      .find(|c: char| matches!(c, '&' | '#' | ')' | '"' | '\'') || c.is_whitespace())
+      .map_or("", |end| &tail[end..]);
+  }
+  out.push_str(rest);
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::secret::{without_api_key_param, ApiKey};
+  use url::Url;
+
+  #[test]
+  fn redact_key() -> color_eyre::Result<()> {
+    let key = ApiKey::from("d4e3b1a2-secret");
+    assert_eq!(format!("{key:?}"), "ApiKey(***)");
+    assert_eq!(key.expose_secret(), "d4e3b1a2-secret");
+    assert_eq!(key.redact("bad key d4e3b1a2-secret"), "bad key ***");
+
+    let mut url = Url::parse("https://mainnet.helius-rpc.com/?api-key=d4e3b1a2-secret&limit=2")?;
+    key.redact_url(&mut url);
+    assert_eq!(url.as_str(), "https://mainnet.helius-rpc.com/?limit=2");
+    let mut url = Url::parse("https://node.example.com/d4e3b1a2-secret/rpc")?;
+    key.redact_url(&mut url);
+    assert!(!url.as_str().contains("secret"));
+
+    let text = "error sending request for url (http://localhost/?api-key=d4e3b1a2-secret&limit=2): refused";
+    assert_eq!(
+      without_api_key_param(text),
+      "error sending request for url (http://localhost/?api-key=***&limit=2): refused"
+    );
+    assert_eq!(without_api_key_param("http://localhost/?api-key=d4e3b1a2-secret"), "http://localhost/?api-key=***");
+    Ok(())
+  }
+}
@@ -0,0 +1,615 @@
+//! Offline stand-in for the Helius API, enabled with the `testing` feature
+//!
+//! [`MockHelius`] serves the REST routes (`/v0/webhooks`, `/v0/transactions`,
+//! `/v0/addresses/{address}/transactions`, `/v0/addresses/{address}/names`) and the DAS JSON-RPC methods
+//! from an in-memory store, seeded with the fixtures in `tests/fixtures`.
+//! REST requests are rejected with 401 unless their `api-key` query parameter is [`API_KEY`].
+//!
+//! ```rust
+//! use selene_helius_sdk::api::das::GetAssetParams;
+//! use selene_helius_sdk::testing::MockHelius;
+//!
+//! #[tokio::main]
+//! async fn main() -> selene_helius_sdk::Result<()> {
+//!   let mock = MockHelius::start().await?;
+//!   let helius = mock.builder().build()?;
+//!   let id = String::from("F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk");
+//!   let asset = helius.get_asset(&GetAssetParams { id: id.clone(), display_options: None }).await?;
+//!   assert_eq!(asset.map(|a| a.id), Some(id));
+//!   Ok(())
+//! }
+//! ```
+use crate::api::das::{
+  GetAssetBatchParams, GetAssetParams, GetAssetProofBatchParams, GetAssetProofParams, GetAssetProofResponse,
+  GetAssetResponse, GetAssetsByAuthorityParams, GetAssetsByCreatorParams, GetAssetsByGroupParams,
+  GetAssetsByOwnerParams, GetPriorityFeeEstimateOptions, GetPriorityFeeEstimateRequest, GetTokenAccountsParams,
+  MicroLamportPriorityFeeLevels, Pagination, SearchAssetsParams, TokenAccount,
+};
+use crate::api::types::enhanced::ParseTransactionsRequest;
+use crate::api::webhook::{Webhook, WebhookData};
+use crate::error::HeliusError;
+use crate::{Cluster, HeliusBuilder};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::StatusCode;
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+const ASSET_FIXTURE: &str = include_str!("../tests/fixtures/das_asset.json");
+const ENHANCED_FIXTURE: &str = include_str!("../tests/fixtures/webhook_enhanced.json");
+const MAX_LIMIT: u32 = 1000;
+
+/// Api key accepted by the REST routes of [`MockHelius`]
+pub const API_KEY: &str = "mock-api-key";
+
+/// JSON-RPC error codes returned by [`MockHelius`]
+const METHOD_NOT_FOUND: i32 = -32601;
+const INVALID_PARAMS: i32 = -32602;
+
+#[derive(Default)]
+struct Store {
+  webhooks: Vec<Webhook>,
+  next_webhook: usize,
+  transactions: Vec<Value>,
+  names: HashMap<String, Vec<String>>,
+  assets: Vec<GetAssetResponse>,
+  proofs: HashMap<String, GetAssetProofResponse>,
+  token_accounts: Vec<TokenAccount>,
+  failures: Vec<(String, StatusCode, usize)>,
+}
+
+type Shared = Arc<Mutex<Store>>;
+
+fn lock(store: &Shared) -> MutexGuard<'_, Store> {
+  store.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn fixture<T: DeserializeOwned>(text: &str) -> crate::Result<T> {
+  serde_json::from_str(text).map_err(|err| HeliusError::SerdeJson { err, text: text.to_string() })
+}
+
+impl Store {
+  fn seeded() -> crate::Result<Self> {
+    let asset: GetAssetResponse = fixture(ASSET_FIXTURE)?;
+    let token_account = TokenAccount {
+      address: String::from("BxGiKCXnVZzB4LvsUBpJ8vh8VpngUmZsDyKKi4sWvD4v"),
+      mint: asset.id.clone(),
+      owner: asset.ownership.owner.clone(),
+      amount: 1,
+      delegated_amount: 0,
+      frozen: asset.ownership.frozen,
+    };
+    Ok(Self {
+      transactions: fixture(ENHANCED_FIXTURE)?,
+      names: HashMap::from([(
+        String::from("86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY"),
+        vec![String::from("toly")],
+      )]),
+      assets: vec![asset],
+      token_accounts: vec![token_account],
+      ..Default::default()
+    })
+  }
+
+  /// Consume one injected failure for the first route accepted by `matches`
+  fn take_failure(&mut self, matches: impl Fn(&str) -> bool) -> Option<StatusCode> {
+    let idx = self.failures.iter().position(|(route, _, _)| matches(route))?;
+    let (_, status, remaining) = &mut self.failures[idx];
+    let status = *status;
+    *remaining -= 1;
+    if *remaining == 0 {
+      self.failures.remove(idx);
+    }
+    Some(status)
+  }
+}
+
+/// Local Helius server for integration tests, see the [module docs](self).
+/// The server is stopped when dropped.
+pub struct MockHelius {
+  addr: SocketAddr,
+  store: Shared,
+  task: JoinHandle<()>,
+}
+
+impl MockHelius {
+  /// Start the server on a random localhost port
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::Io`] if the port cannot be bound
+  pub async fn start() -> crate::Result<Self> {
+    let store = Arc::new(Mutex::new(Store::seeded()?));
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let app = router(store.clone());
+    let task = tokio::spawn(async move {
+      if let Err(err) = axum::serve(listener, app).await {
+        tracing::error!("mock helius server stopped {err}");
+      }
+    });
+    Ok(Self { addr, store, task })
+  }
+
+  /// Base url of the REST API
+  pub fn api_url(&self) -> String {
+    format!("http://{}/v0", self.addr)
+  }
+
+  /// JSON-RPC endpoint
+  pub fn rpc_url(&self) -> String {
+    format!("http://{}/", self.addr)
+  }
+
+  /// [`Cluster::Custom`] pointing at this server
+  pub fn cluster(&self) -> Cluster {
+    Cluster::Custom { rpc: self.rpc_url(), api: self.api_url() }
+  }
+
+  /// [`HeliusBuilder`] pointing at this server
+  pub fn builder(&self) -> HeliusBuilder {
+    HeliusBuilder::new(API_KEY).cluster(self.cluster())
+  }
+
+  /// Respond with `status` to the next `times` requests of `route`.
+  /// `route` is either a DAS method, such as `getAsset`, or the prefix of a REST path, such as `webhooks`
+  pub fn fail(&self, route: &str, status: StatusCode, times: usize) {
+    if times > 0 {
+      lock(&self.store).failures.push((route.to_string(), status, times));
+    }
+  }
+
+  pub fn add_asset(&self, asset: GetAssetResponse) {
+    lock(&self.store).assets.push(asset);
+  }
+
+  pub fn add_asset_proof(&self, id: &str, proof: GetAssetProofResponse) {
+    lock(&self.store).proofs.insert(id.to_string(), proof);
+  }
+
+  pub fn add_token_account(&self, account: TokenAccount) {
+    lock(&self.store).token_accounts.push(account);
+  }
+
+  /// Add an enhanced transaction, transactions are returned newest first in the order they were added
+  pub fn add_transaction(&self, transaction: Value) {
+    lock(&self.store).transactions.push(transaction);
+  }
+
+  pub fn set_names(&self, address: &str, names: Vec<String>) {
+    lock(&self.store).names.insert(address.to_string(), names);
+  }
+
+  /// Webhooks currently registered
+  pub fn webhooks(&self) -> Vec<Webhook> {
+    lock(&self.store).webhooks.clone()
+  }
+}
+
+impl Drop for MockHelius {
+  fn drop(&mut self) {
+    self.task.abort();
+  }
+}
+
+fn router(store: Shared) -> Router {
+  let api = Router::new()
+    .route("/webhooks", get(list_webhooks).post(create_webhook))
+    .route("/webhooks/:id", get(get_webhook).put(edit_webhook).delete(delete_webhook))
+    .route("/transactions", post(parse_transactions))
+    .route("/addresses/:address/transactions", get(transaction_history))
+    .route("/addresses/:address/names", get(names))
+    .route_layer(middleware::from_fn_with_state(store.clone(), inject_failure))
+    .route_layer(middleware::from_fn(require_api_key));
+  Router::new().nest("/v0", api).route("/", post(rpc)).with_state(store)
+}
+
+async fn inject_failure(State(store): State<Shared>, req: Request, next: Next) -> Response {
+  let path = req.uri().path().trim_start_matches("/v0").trim_start_matches('/').to_string();
+  let failure = lock(&store).take_failure(|route| path.starts_with(route));
+  match failure {
+    Some(status) => (status, "injected failure").into_response(),
+    None => next.run(req).await,
+  }
+}
+
+async fn require_api_key(Query(query): Query<HashMap<String, String>>, req: Request, next: Next) -> Response {
+  if query.get("api-key").map(String::as_str) == Some(API_KEY) {
+    next.run(req).await
+  } else {
+    (StatusCode::UNAUTHORIZED, "invalid api key").into_response()
+  }
+}
+
+fn not_found() -> Response {
+  (StatusCode::NOT_FOUND, "not found").into_response()
+}
+
+async fn list_webhooks(State(store): State<Shared>) -> Json<Vec<Webhook>> {
+  Json(lock(&store).webhooks.clone())
+}
+
+async fn create_webhook(State(store): State<Shared>, Json(data): Json<WebhookData>) -> Json<Webhook> {
+  let mut store = lock(&store);
+  store.next_webhook += 1;
+  let webhook = Webhook {
+    webhook_id: format!("mock-webhook-{}", store.next_webhook),
+    wallet: String::from("mock-wallet"),
+    webhook_data: data,
+  };
+  store.webhooks.push(webhook.clone());
+  drop(store);
+  Json(webhook)
+}
+
+async fn get_webhook(State(store): State<Shared>, Path(id): Path<String>) -> Response {
+  let store = lock(&store);
+  store.webhooks.iter().find(|w| w.webhook_id == id).map_or_else(not_found, |w| Json(w.clone()).into_response())
+}
+
+async fn edit_webhook(State(store): State<Shared>, Path(id): Path<String>, Json(data): Json<WebhookData>) -> Response {
+  let mut store = lock(&store);
+  match store.webhooks.iter_mut().find(|w| w.webhook_id == id) {
+    None => not_found(),
+    Some(webhook) => {
+      webhook.webhook_data = data;
+      Json(webhook.clone()).into_response()
+    },
+  }
+}
+
+async fn delete_webhook(State(store): State<Shared>, Path(id): Path<String>) -> StatusCode {
+  let mut store = lock(&store);
+  let before = store.webhooks.len();
+  store.webhooks.retain(|w| w.webhook_id != id);
+  if store.webhooks.len() == before {
+    StatusCode::NOT_FOUND
+  } else {
+    StatusCode::OK
+  }
+}
+
+async fn parse_transactions(
+  State(store): State<Shared>,
+  Json(request): Json<ParseTransactionsRequest>,
+) -> Json<Vec<Value>> {
+  let store = lock(&store);
+  let parsed = request
+    .transactions
+    .iter()
+    .filter_map(|sig| store.transactions.iter().find(|t| t["signature"] == sig.as_str()))
+    .cloned()
+    .collect();
+  Json(parsed)
+}
+
+fn involves(transaction: &Value, address: &str) -> bool {
+  transaction["feePayer"] == address
+    || transaction["accountData"].as_array().is_some_and(|data| data.iter().any(|d| d["account"] == address))
+}
+
+async fn transaction_history(
+  State(store): State<Shared>,
+  Path(address): Path<String>,
+  Query(query): Query<HashMap<String, String>>,
+) -> Json<Vec<Value>> {
+  let store = lock(&store);
+  let limit = query.get("limit").and_then(|l| l.parse().ok()).unwrap_or(100);
+  let mut history = store.transactions.iter().rev().filter(|t| involves(t, &address)).peekable();
+  if let Some(before) = query.get("before") {
+    while history.next_if(|t| t["signature"] != before.as_str()).is_some() {}
+    history.next();
+  }
+  let until = query.get("until");
+  let page = history
+    .take_while(|t| until.is_none_or(|u| t["signature"] != u.as_str()))
+    .filter(|t| query.get("type").is_none_or(|kind| t["type"] == kind.as_str()))
+    .filter(|t| query.get("source").is_none_or(|source| t["source"] == source.as_str()))
+    .take(limit)
+    .cloned()
+    .collect();
+  drop(store);
+  Json(page)
+}
+
+async fn names(State(store): State<Shared>, Path(address): Path<String>) -> Json<Value> {
+  let names = lock(&store).names.get(&address).cloned().unwrap_or_default();
+  Json(json!({ "domainNames": names }))
+}
+
+type RpcResult = std::result::Result<Value, (i32, String)>;
+
+/// Handle a single JSON-RPC call or a batch of calls
+async fn rpc(State(store): State<Shared>, Json(request): Json<Value>) -> Response {
+  let calls = request.as_array().map_or_else(|| std::slice::from_ref(&request), Vec::as_slice);
+  let mut store = lock(&store);
+  let failure = calls.iter().find_map(|call| {
+    let method = call["method"].as_str().unwrap_or_default();
+    store.take_failure(|route| route == method)
+  });
+  if let Some(status) = failure {
+    return (status, "injected failure").into_response();
+  }
+  let mut responses: Vec<Value> = calls.iter().map(|call| respond(&store, call)).collect();
+  drop(store);
+  if request.is_array() {
+    Json(Value::Array(responses)).into_response()
+  } else {
+    Json(responses.pop().unwrap_or_default()).into_response()
+  }
+}
+
+fn respond(store: &Store, call: &Value) -> Value {
+  let id = call["id"].clone();
+  match dispatch(store, call["method"].as_str().unwrap_or_default(), call["params"].clone()) {
+    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+    Err((code, message)) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } }),
+  }
+}
+
+fn params<T: DeserializeOwned>(params: Value) -> std::result::Result<T, (i32, String)> {
+  serde_json::from_value(params).map_err(|e| (INVALID_PARAMS, format!("Invalid params: {e}")))
+}
+
+fn to_value<T: Serialize>(value: &T) -> RpcResult {
+  serde_json::to_value(value).map_err(|e| (INVALID_PARAMS, e.to_string()))
+}
+
+fn dispatch(store: &Store, method: &str, p: Value) -> RpcResult {
+  let find = |id: &str| store.assets.iter().find(|a| a.id == id);
+  match method {
+    "getAsset" => to_value(&find(&params::<GetAssetParams>(p)?.id)),
+    "getAssetBatch" => to_value(&params::<GetAssetBatchParams>(p)?.ids.iter().map(|id| find(id)).collect::<Vec<_>>()),
+    "getAssetProof" => to_value(&store.proofs.get(&params::<GetAssetProofParams>(p)?.id)),
+    "getAssetProofBatch" => {
+      let ids = params::<GetAssetProofBatchParams>(p)?.ids;
+      to_value(&ids.iter().filter_map(|id| store.proofs.get_key_value(id)).collect::<HashMap<_, _>>())
+    },
+    "getAssetsByOwner" => {
+      let p: GetAssetsByOwnerParams = params(p)?;
+      asset_page(store, &p.pagination, |a| a.ownership.owner == p.owner_address)
+    },
+    "getAssetsByAuthority" => {
+      let p: GetAssetsByAuthorityParams = params(p)?;
+      asset_page(store, &p.pagination, |a| {
+        a.authorities.iter().flatten().any(|auth| auth.address == p.authority_address)
+      })
+    },
+    "getAssetsByCreator" => {
+      let p: GetAssetsByCreatorParams = params(p)?;
+      asset_page(store, &p.pagination, |a| {
+        a.creators.iter().flatten().any(|c| c.address == p.creator_address && (c.verified || !p.only_verified))
+      })
+    },
+    "getAssetsByGroup" => {
+      let p: GetAssetsByGroupParams = params(p)?;
+      asset_page(store, &p.pagination, |a| {
+        a.grouping.iter().flatten().any(|g| g.group_key == p.group_key && g.group_value == p.group_value)
+      })
+    },
+    "searchAssets" => {
+      let p: SearchAssetsParams = params(p)?;
+      let mut page = asset_page(store, &p.pagination, |a| {
+        p.owner_address.as_ref().is_none_or(|owner| &a.ownership.owner == owner)
+          && p.creator_address.as_ref().is_none_or(|creator| a.creators.iter().flatten().any(|c| &c.address == creator))
+          && p.compressed.is_none_or(|compressed| a.compression.as_ref().is_some_and(|c| c.compressed == compressed))
+          && p.burnt.is_none_or(|burnt| a.burnt == burnt)
+      })?;
+      if p.display_options.is_some_and(|d| d.show_native_balance) {
+        page["nativeBalance"] = json!({ "lamports": 1_000_000_000, "price_per_sol": 150, "total_price": 150 });
+      }
+      Ok(page)
+    },
+    "getTokenAccounts" => {
+      let p: GetTokenAccountsParams = params(p)?;
+      let limit = p.limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT);
+      let accounts: Vec<&TokenAccount> = store
+        .token_accounts
+        .iter()
+        .filter(|t| p.owner.as_ref().is_none_or(|o| &t.owner == o))
+        .filter(|t| p.mint.as_ref().is_none_or(|m| &t.mint == m))
+        .skip((p.page.saturating_sub(1) * limit) as usize)
+        .take(limit as usize)
+        .collect();
+      Ok(json!({ "total": accounts.len(), "limit": limit, "page": p.page, "token_accounts": accounts }))
+    },
+    "getPriorityFeeEstimate" => {
+      let requests: Vec<GetPriorityFeeEstimateRequest> = params(p)?;
+      match requests.first().map(|r| &r.options) {
+        None => Err((INVALID_PARAMS, String::from("Invalid params: missing request"))),
+        Some(GetPriorityFeeEstimateOptions::AllFeeLevels(_)) => {
+          let levels = MicroLamportPriorityFeeLevels {
+            low: 1_000.0,
+            medium: 10_000.0,
+            high: 100_000.0,
+            very_high: 1_000_000.0,
+            unsafe_max: 10_000_000.0,
+          };
+          Ok(json!({ "priorityFeeLevels": levels }))
+        },
+        Some(GetPriorityFeeEstimateOptions::Priority(_)) => Ok(json!({ "priorityFeeEstimate": 100_000.0 })),
+      }
+    },
+    _ => Err((METHOD_NOT_FOUND, String::from("Method not found"))),
+  }
+}
+
+/// Apply the DAS paging rules: `before`/`after` cursors on asset ids, otherwise `page` and `limit`
+fn asset_page(store: &Store, pagination: &Pagination, filter: impl Fn(&GetAssetResponse) -> bool) -> RpcResult {
+  if pagination.page.is_some() && (pagination.before.is_some() || pagination.after.is_some()) {
+    return Err((INVALID_PARAMS, String::from("Invalid params: page and before/after cannot be used together")));
+  }
+  let limit = pagination.limit.unwrap_or(MAX_LIMIT).min(MAX_LIMIT);
+  let matching: Vec<&GetAssetResponse> = store.assets.iter().filter(|a| filter(a)).collect();
+  let items: Vec<&GetAssetResponse> = match (&pagination.after, &pagination.before) {
+    (Some(after), _) => matching.iter().skip_while(|a| &a.id != after).skip(1).take(limit as usize).copied().collect(),
+    (None, Some(before)) => matching.iter().take_while(|a| &a.id != before).take(limit as usize).copied().collect(),
+    (None, None) => matching
+      .iter()
+      .skip((pagination.page.unwrap_or(1).saturating_sub(1) * limit) as usize)
+      .take(limit as usize)
+      .copied()
+      .collect(),
+  };
+  Ok(json!({ "total": items.len(), "limit": limit, "page": pagination.page, "items": items }))
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::{
+    DasRequest, DasResponse, GetAssetBatchParams, GetAssetParams, GetAssetProofParams, GetAssetResponse,
+    GetAssetsByGroupParams, GetAssetsByOwnerParams, GetTokenAccountsParams, PageOptions, PriorityLevel,
+  };
+  use crate::api::types::enhanced::{ParseTransactionsRequest, TransactionHistoryOptions};
+  use crate::api::webhook::{CreateWebhookRequest, EditWebhookRequest, WebhookData};
+  use crate::error::HeliusError;
+  use crate::retry::RetryPolicy;
+  use crate::testing::MockHelius;
+  use crate::HeliusBuilder;
+  use futures::TryStreamExt;
+  use reqwest::StatusCode;
+  use std::time::Duration;
+
+  const MAD_LAD: &str = "F9Lw3ki3hJ7PF9HQXsBzoY8GyE6sPoEZZdXJBsTTD2rk";
+  const SIGNATURE: &str = "5nNtjezQMYBHvgSQmoRmJPiXGsPAWmJPoGSa64xanqrauogiVzFyGQhKeFataHGXq51jR2hjbzNTkPUpP787HAmL";
+
+  #[tokio::test]
+  async fn mock_webhooks() -> color_eyre::Result<()> {
+    let mock = MockHelius::start().await?;
+    let helius = mock.builder().build()?;
+    let data = WebhookData {
+      webhook_url: String::from("https://localhost:3000"),
+      account_addresses: vec![String::from("a")],
+      ..Default::default()
+    };
+    let hook = helius.create_webhook(&CreateWebhookRequest { data }).await?;
+    assert_eq!(helius.get_all_webhooks().await?.len(), 1);
+    let hook = helius.append_addresses_to_webhook(&hook.webhook_id, &[String::from("b")]).await?;
+    assert_eq!(hook.webhook_data.account_addresses, vec!["a", "b"]);
+    let mut data = hook.webhook_data.clone();
+    data.webhook_url = String::from("https://localhost:4000");
+    helius.edit_webhook(&EditWebhookRequest { webhook_id: hook.webhook_id.clone(), data }).await?;
+    assert_eq!(mock.webhooks()[0].webhook_data.webhook_url, "https://localhost:4000");
+    helius.delete_webhook(&hook.webhook_id).await?;
+    assert!(helius.get_all_webhooks().await?.is_empty());
+    assert!(matches!(helius.get_webhook_by_id(&hook.webhook_id).await, Err(HeliusError::NotFound { .. })));
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn mock_enhanced_transactions() -> color_eyre::Result<()> {
+    let mock = MockHelius::start().await?;
+    let helius = mock.builder().build()?;
+    let sigs = vec![SIGNATURE.to_string(), String::from("unknown")];
+    let parsed = helius.parse_transaction(&ParseTransactionsRequest { transactions: sigs }).await?;
+    assert_eq!(parsed.len(), 1);
+
+    let history = helius.parsed_transaction_history("CKs1E69a2e9TmH4mKKLrXFF8kD3ZnwKjoEuXa6sz9WqX").await?;
+    assert_eq!(history[0].signature, SIGNATURE);
+    let opts = TransactionHistoryOptions { before: Some(SIGNATURE.to_string()), ..Default::default() };
+    let history =
+      helius.parsed_transaction_history_with_options("CKs1E69a2e9TmH4mKKLrXFF8kD3ZnwKjoEuXa6sz9WqX", &opts).await?;
+    assert!(history.is_empty());
+
+    let names = helius.get_names("86xCnPeV69n6t3DnyGvkKobf9FdN2H9oiVDdaMpo2MMY").await?;
+    assert_eq!(names.domain_names, vec!["toly"]);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn mock_das() -> color_eyre::Result<()> {
+    let mock = MockHelius::start().await?;
+    let helius = mock.builder().build()?;
+    let asset = helius.get_asset(&GetAssetParams { id: MAD_LAD.to_string(), display_options: None }).await?;
+    assert_eq!(asset.map(|a| a.id), Some(MAD_LAD.to_string()));
+
+    let ids = vec![MAD_LAD.to_string(), String::from("missing")];
+    let batch = helius.get_asset_batch(&GetAssetBatchParams { ids, display_options: None }).await?;
+    assert!(batch[0].is_some() && batch[1].is_none());
+
+    for n in 0..5 {
+      mock.add_asset(GetAssetResponse {
+        id: format!("asset-{n}"),
+        ownership: crate::api::das::Ownership { owner: String::from("owner"), ..Default::default() },
+        ..Default::default()
+      });
+    }
+    let params = GetAssetsByOwnerParams { owner_address: String::from("owner"), ..Default::default() };
+    let assets: Vec<GetAssetResponse> =
+      helius.get_assets_by_owner_stream(&params, PageOptions { page_size: 2, max_items: None }).try_collect().await?;
+    assert_eq!(assets.len(), 5);
+
+    let group = helius
+      .get_assets_by_group(&GetAssetsByGroupParams {
+        group_key: String::from("collection"),
+        group_value: String::from("J1S9H3QjnRtBbbuD4HjPV6RpRhwuk4zKbxsnCHuTgh9w"),
+        ..Default::default()
+      })
+      .await?;
+    assert_eq!(group.items.len(), 1);
+
+    let accounts = helius
+      .get_token_accounts(&GetTokenAccountsParams { mint: Some(MAD_LAD.to_string()), ..Default::default() })
+      .await?;
+    assert_eq!(accounts.token_accounts.len(), 1);
+
+    let batch = helius
+      .das_batch()
+      .get_asset(GetAssetParams { id: MAD_LAD.to_string(), display_options: None })
+      .push(DasRequest::GetAssetProof(GetAssetProofParams { id: MAD_LAD.to_string() }))
+      .send()
+      .await?;
+    assert!(matches!(&batch[0], Ok(DasResponse::Asset(Some(_)))));
+    assert!(matches!(&batch[1], Ok(DasResponse::AssetProof(None))));
+
+    assert!(helius.get_estimate_priority_fee_levels(vec![String::from("a")]).await?.high > 0.0);
+    assert!(helius.get_estimate_priority_fee(vec![String::from("a")], PriorityLevel::High).await? > 0.0);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn mock_failures() -> color_eyre::Result<()> {
+    let mock = MockHelius::start().await?;
+    let helius = mock.builder().build()?;
+    mock.fail("webhooks", StatusCode::UNAUTHORIZED, 1);
+    assert!(matches!(helius.get_all_webhooks().await, Err(HeliusError::Unauthorized { .. })));
+    assert!(helius.get_all_webhooks().await?.is_empty());
+
+    mock.fail("getAsset", StatusCode::TOO_MANY_REQUESTS, 2);
+    let params = GetAssetParams { id: MAD_LAD.to_string(), display_options: None };
+    assert!(matches!(helius.get_asset(&params).await, Err(HeliusError::TooManyRequests { .. })));
+    let helius = mock
+      .builder()
+      .retry_policy(RetryPolicy { base_delay: Duration::from_millis(1), ..Default::default() })
+      .build()?;
+    assert!(helius.get_asset(&params).await?.is_some());
+
+    let body = serde_json::json!({ "jsonrpc": "2.0", "id": "1", "method": "notAMethod", "params": {} });
+    let res: serde_json::Value = reqwest::Client::new().post(mock.rpc_url()).json(&body).send().await?.json().await?;
+    assert_eq!(res["error"]["code"], -32601);
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn mock_requires_api_key() -> color_eyre::Result<()> {
+    let mock = MockHelius::start().await?;
+    let status = reqwest::Client::new().get(format!("{}/webhooks", mock.api_url())).send().await?.status();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let helius = HeliusBuilder::new("wrong-api-key").cluster(mock.cluster()).build()?;
+    let err = helius.get_all_webhooks().await.expect_err("wrong api key");
+    assert!(matches!(err, HeliusError::Unauthorized { .. }));
+    assert!(!err.to_string().contains("wrong-api-key"));
+    assert!(mock.builder().build()?.get_all_webhooks().await?.is_empty());
+    Ok(())
+  }
+}
@@ -0,0 +1,284 @@
+//! HTTP transport used by the REST API and DAS calls.
+//!
+//! [`HeliusBuilder::transport`](crate::HeliusBuilder::transport) replaces the default [`ReqwestTransport`],
+//! e.g. with a [`RecordingTransport`] to capture live traffic into a fixture file
+//! and a [`ReplayTransport`] to serve it back in offline tests.
+//!
+//! ```rust,no_run
+//! use selene_helius_sdk::transport::{RecordingTransport, ReplayTransport, ReqwestTransport};
+//! use selene_helius_sdk::HeliusBuilder;
+//!
+//! # fn run() -> selene_helius_sdk::Result<()> {
+//! let recorder = RecordingTransport::new(ReqwestTransport::new(reqwest::Client::new()), "tests/fixtures/assets.json");
+//! let live = HeliusBuilder::new("api-key").transport(recorder).build()?;
+//!
+//! let offline = HeliusBuilder::new("api-key").transport(ReplayTransport::load("tests/fixtures/assets.json")?).build()?;
+//! # Ok(())
+//! # }
+//! ```
+//! Solana RPC calls made through [`Helius::connection`](crate::Helius::connection) do not go through the transport.
+use crate::error::HeliusError;
+use crate::secret::without_api_key;
+use crate::Result;
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use url::Url;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HttpRequest {
+  pub method: Method,
+  pub url: Url,
+  pub headers: HeaderMap,
+  /// JSON body
+  pub body: Option<String>,
+}
+
+impl HttpRequest {
+  pub fn new(method: Method, url: Url, body: Option<String>) -> Self {
+    Self { method, url, headers: HeaderMap::new(), body }
+  }
+}
+
+#[derive(Clone, Debug)]
+pub struct HttpResponse {
+  pub status: StatusCode,
+  pub headers: HeaderMap,
+  pub body: String,
+}
+
+/// Sends a request and returns the raw response, status codes are handled by the caller
+#[async_trait]
+pub trait Transport: Send + Sync {
+  async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+}
+
+#[async_trait]
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+  async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+    (**self).send(request).await
+  }
+}
+
+/// The default [`Transport`]
+#[derive(Clone, Debug)]
+pub struct ReqwestTransport {
+  client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+  pub const fn new(client: reqwest::Client) -> Self {
+    Self { client }
+  }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+  async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+    let mut req = self.client.request(request.method, request.url).headers(request.headers);
+    if let Some(body) = request.body {
+      req = req.header(CONTENT_TYPE, "application/json").body(body);
+    }
+    let resp = req.send().await?;
+    let status = resp.status();
+    let headers = resp.headers().clone();
+    Ok(HttpResponse { status, headers, body: resp.text().await? })
+  }
+}
+
+/// A request/response pair of a fixture file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Interaction {
+  pub request: RecordedRequest,
+  pub response: RecordedResponse,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecordedRequest {
+  pub method: String,
+  /// Url without the `api-key` query parameter
+  pub url: String,
+  pub body: Option<Value>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct RecordedResponse {
+  pub status: u16,
+  #[serde(default)]
+  pub headers: BTreeMap<String, String>,
+  pub body: String,
+}
+
+impl RecordedRequest {
+  fn new(request: &HttpRequest) -> Self {
+    let url = without_api_key(&request.url);
+    let body = request.body.as_deref().map(|b| serde_json::from_str(b).unwrap_or_else(|_| Value::from(b)));
+    Self { method: request.method.to_string(), url: url.to_string(), body }
+  }
+}
+
+impl RecordedResponse {
+  fn new(response: &HttpResponse) -> Self {
+    let headers =
+      response.headers.iter().filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string()))).collect();
+    Self { status: response.status.as_u16(), headers, body: response.body.clone() }
+  }
+
+  fn to_response(&self) -> HttpResponse {
+    let headers = self
+      .headers
+      .iter()
+      .filter_map(|(k, v)| Some((HeaderName::try_from(k).ok()?, HeaderValue::try_from(v).ok()?)))
+      .collect();
+    HttpResponse {
+      status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+      headers,
+      body: self.body.clone(),
+    }
+  }
+}
+
+/// Sends requests through another [`Transport`] and writes every interaction to a JSON fixture file.
+/// The file is rewritten after each response, the `api-key` query parameter is not recorded.
+pub struct RecordingTransport {
+  inner: Box<dyn Transport>,
+  path: PathBuf,
+  interactions: Mutex<Vec<Interaction>>,
+  /// Held while the file is written, so a slow write cannot overwrite a newer one
+  writing: tokio::sync::Mutex<()>,
+}
+
+impl RecordingTransport {
+  pub fn new(inner: impl Transport + 'static, path: impl Into<PathBuf>) -> Self {
+    Self {
+      inner: Box::new(inner),
+      path: path.into(),
+      interactions: Mutex::new(Vec::new()),
+      writing: tokio::sync::Mutex::new(()),
+    }
+  }
+
+  /// Interactions recorded so far
+  pub fn interactions(&self) -> Vec<Interaction> {
+    self.interactions.lock().unwrap_or_else(PoisonError::into_inner).clone()
+  }
+}
+
+#[async_trait]
+impl Transport for RecordingTransport {
+  async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+    let recorded = RecordedRequest::new(&request);
+    let response = self.inner.send(request).await?;
+    let interaction = Interaction { request: recorded, response: RecordedResponse::new(&response) };
+    self.interactions.lock().unwrap_or_else(PoisonError::into_inner).push(interaction);
+    let _writing = self.writing.lock().await;
+    let json = serde_json::to_string_pretty(&self.interactions())
+      .map_err(|err| HeliusError::SerdeJson { err, text: self.path.display().to_string() })?;
+    tokio::fs::write(&self.path, json).await?;
+    Ok(response)
+  }
+}
+
+/// Serves the responses of a fixture file written by [`RecordingTransport`] without any network access.
+/// Requests are matched on method, url and body, each interaction is served once in recorded order.
+pub struct ReplayTransport {
+  interactions: Mutex<Vec<Option<Interaction>>>,
+}
+
+impl ReplayTransport {
+  pub fn new(interactions: Vec<Interaction>) -> Self {
+    Self { interactions: Mutex::new(interactions.into_iter().map(Some).collect()) }
+  }
+
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::Io`] if the file cannot be read,
+  /// [`HeliusError::SerdeJson`] if it is not a list of [`Interaction`]
+  pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+    let text = std::fs::read_to_string(path.into())?;
+    let interactions = serde_json::from_str(&text).map_err(|err| HeliusError::SerdeJson { err, text })?;
+    Ok(Self::new(interactions))
+  }
+
+  /// Number of interactions that have not been served yet
+  pub fn remaining(&self) -> usize {
+    self.interactions.lock().unwrap_or_else(PoisonError::into_inner).iter().flatten().count()
+  }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+  async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+    let recorded = RecordedRequest::new(&request);
+    let found = {
+      let mut interactions = self.interactions.lock().unwrap_or_else(PoisonError::into_inner);
+      interactions.iter_mut().find(|i| i.as_ref().is_some_and(|i| i.request == recorded)).and_then(Option::take)
+    };
+    match found {
+      Some(interaction) => Ok(interaction.response.to_response()),
+      None => Err(HeliusError::NoRecordedResponse { method: recorded.method, url: recorded.url }),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::das::{GetAssetParams, GetAssetResponse};
+  use crate::error::HeliusError;
+  use crate::transport::{RecordingTransport, ReplayTransport, ReqwestTransport};
+  use crate::HeliusBuilder;
+  use color_eyre::eyre::format_err;
+  use serde_json::json;
+
+  const ASSET: &str = include_str!("../tests/fixtures/das_asset.json");
+
+  #[tokio::test]
+  async fn record_and_replay() -> color_eyre::Result<()> {
+    let mut server = mockito::Server::new_async().await;
+    let asset: GetAssetResponse = serde_json::from_str(ASSET)?;
+    let mock = server
+      .mock("POST", "/")
+      .match_query(mockito::Matcher::UrlEncoded("api-key".into(), "secret".into()))
+      .with_body(json!({"jsonrpc": "2.0", "id": "1", "result": asset}).to_string())
+      .expect(1)
+      .create_async()
+      .await;
+    let path = std::env::temp_dir().join(format!("helius-record-{}.json", rand::random::<u64>()));
+    let params = GetAssetParams { id: asset.id.clone(), display_options: None };
+
+    let recorder = RecordingTransport::new(ReqwestTransport::new(reqwest::Client::new()), &path);
+    let rpc_url = format!("{}/?api-key=secret", server.url());
+    let live = HeliusBuilder::new("secret").rpc_url(&rpc_url).transport(recorder).build()?;
+    assert_eq!(live.get_asset(&params).await?, Some(asset.clone()));
+    mock.assert_async().await;
+    let fixture = std::fs::read_to_string(&path)?;
+    assert!(!fixture.contains("secret"));
+    drop(server);
+
+    let replay = ReplayTransport::load(&path)?;
+    std::fs::remove_file(&path)?;
+    let offline = HeliusBuilder::new("other").rpc_url(&rpc_url).transport(replay).build()?;
+    assert_eq!(offline.get_asset(&params).await?, Some(asset));
+    match offline.get_asset(&params).await {
+      Err(HeliusError::NoRecordedResponse { method, .. }) => assert_eq!(method, "POST"),
+      _ => return Err(format_err!("second replay should fail")),
+    }
+    Ok(())
+  }
+
+  #[test]
+  fn load_invalid_fixture() -> color_eyre::Result<()> {
+    let path = std::env::temp_dir().join(format!("helius-replay-{}.json", rand::random::<u64>()));
+    std::fs::write(&path, "{}")?;
+    let loaded = ReplayTransport::load(&path);
+    std::fs::remove_file(&path)?;
+    assert!(matches!(loaded, Err(HeliusError::SerdeJson { .. })));
+    assert!(matches!(ReplayTransport::load(&path), Err(HeliusError::Io(_))));
+    Ok(())
+  }
+}
@@ -0,0 +1,254 @@
+//! HTTP listener for the webhook POSTs Helius sends, enabled with the `webhook-server` feature
+//!
+//! ```rust,no_run
+//! use selene_helius_sdk::webhook_server::{HandlerError, WebhookEvent, WebhookHandler, WebhookServer};
+//! use selene_helius_sdk::api::webhook::WebhookType;
+//!
+//! struct Printer;
+//!
+//! #[async_trait::async_trait]
+//! impl WebhookHandler for Printer {
+//!   async fn handle(&self, event: WebhookEvent) -> Result<(), HandlerError> {
+//!     if let WebhookEvent::Enhanced(txns) = event {
+//!       for t in txns {
+//!         println!("{} {}", t.signature, t.description);
+//!       }
+//!     }
+//!     Ok(())
+//!   }
+//! }
+//!
+//! #[tokio::main]
+//! async fn main() -> selene_helius_sdk::Result<()> {
+//!   let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+//!   WebhookServer::new(Printer, WebhookType::Enhanced).auth_header("secret").serve(listener).await
+//! }
+//! ```
+use crate::api::types::enhanced::EnhancedTransaction;
+use crate::api::types::raw::RawTransaction;
+use crate::api::webhook::{WebhookData, WebhookType};
+use crate::error::HeliusError;
+use crate::webhook_verifier::WebhookVerifier;
+use async_trait::async_trait;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{debug, warn};
+
+/// Typed webhook payload
+#[derive(Debug)]
+pub enum WebhookEvent {
+  /// Payload of [`WebhookType::Enhanced`] and [`WebhookType::EnhancedDevnet`] webhooks
+  Enhanced(Vec<EnhancedTransaction>),
+  /// Payload of [`WebhookType::Raw`] and [`WebhookType::RawDevnet`] webhooks
+  Raw(Vec<RawTransaction>),
+}
+
+impl WebhookEvent {
+  /// Decode a webhook body according to the type of webhook which sent it
+  ///
+  /// # Errors
+  ///
+  /// Will return [`HeliusError::SerdeJson`] when the body does not match the webhook type and
+  /// [`HeliusError::InvalidWebhook`] for Discord webhooks, Helius posts those to Discord and never to a server
+  pub fn parse(webhook_type: &WebhookType, body: &[u8]) -> crate::Result<Self> {
+    let event = match webhook_type {
+      WebhookType::Enhanced | WebhookType::EnhancedDevnet => serde_json::from_slice(body).map(Self::Enhanced),
+      WebhookType::Raw | WebhookType::RawDevnet => serde_json::from_slice(body).map(Self::Raw),
+      WebhookType::Discord | WebhookType::DiscordDevnet => {
+        return Err(HeliusError::InvalidWebhook { message: format!("{webhook_type:?} webhooks have no payload") })
+      },
+    };
+    event.map_err(|err| HeliusError::SerdeJson { err, text: String::new() })
+  }
+}
+
+/// Error returned by a [`WebhookHandler`]
+pub type HandlerError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Receives every [`WebhookEvent`] accepted by the [`WebhookServer`].
+/// Returning an error responds with HTTP 500, so Helius will retry the delivery.
+#[async_trait]
+pub trait WebhookHandler: Send + Sync + 'static {
+  async fn handle(&self, event: WebhookEvent) -> Result<(), HandlerError>;
+}
+
+/// Webhook receiver, see the [module docs](self)
+pub struct WebhookServer<H> {
+  handler: H,
+  webhook_type: WebhookType,
+  auth_header: Option<String>,
+  path: String,
+}
+
+struct Receiver<H> {
+  handler: H,
+  webhook_type: WebhookType,
+  verifier: WebhookVerifier,
+}
+
+impl<H: WebhookHandler> WebhookServer<H> {
+  pub fn new(handler: H, webhook_type: WebhookType) -> Self {
+    Self { handler, webhook_type, auth_header: None, path: String::from("/") }
+  }
+
+  /// Receiver matching the type and auth header of a webhook created with [`crate::Helius::create_webhook`]
+  pub fn from_webhook(data: &WebhookData, handler: H) -> Self {
+    Self { auth_header: data.auth_header.clone(), ..Self::new(handler, data.webhook_type.clone()) }
+  }
+
+  /// Reject requests whose `Authorization` header does not match
+  #[must_use]
+  pub fn auth_header(mut self, auth_header: &str) -> Self {
+    self.auth_header = Some(auth_header.to_string());
+    self
+  }
+
+  /// Path to listen on, defaults to `/`
+  #[must_use]
+  pub fn path(mut self, path: &str) -> Self {
+    self.path = path.to_string();
+    self
+  }
+
+  /// [`Router`] to mount into an existing axum application
+  pub fn router(self) -> Router {
+    let verifier = self.auth_header.as_deref().map(WebhookVerifier::new).unwrap_or_default();
+    let receiver = Arc::new(Receiver { handler: self.handler, webhook_type: self.webhook_type, verifier });
+    Router::new().route(&self.path, post(receive::<H>)).with_state(receiver)
+  }
+
+  /// Serve webhooks until the listener fails
+  ///
+  /// # Errors
+  ///
+  /// Will return [`crate::HeliusError::Io`]
+  pub async fn serve(self, listener: TcpListener) -> crate::Result<()> {
+    axum::serve(listener, self.router()).await?;
+    Ok(())
+  }
+}
+
+async fn receive<H: WebhookHandler>(
+  State(receiver): State<Arc<Receiver<H>>>,
+  headers: HeaderMap,
+  body: Bytes,
+) -> StatusCode {
+  if let Err(err) = receiver.verifier.verify_auth(&headers) {
+    warn!("rejected webhook: {err}");
+    return err.status();
+  }
+  let event = match WebhookEvent::parse(&receiver.webhook_type, &body) {
+    Ok(event) => event,
+    Err(err) => {
+      warn!("invalid webhook payload {err}");
+      return StatusCode::BAD_REQUEST;
+    },
+  };
+  debug!("received webhook {:?}", receiver.webhook_type);
+  match receiver.handler.handle(event).await {
+    Ok(()) => StatusCode::OK,
+    Err(err) => {
+      warn!("webhook handler failed {err}");
+      StatusCode::INTERNAL_SERVER_ERROR
+    },
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::webhook::{WebhookData, WebhookType};
+  use crate::error::HeliusError;
+  use crate::webhook_server::{HandlerError, WebhookEvent, WebhookHandler, WebhookServer};
+  use async_trait::async_trait;
+  use color_eyre::eyre::format_err;
+  use reqwest::StatusCode;
+  use std::sync::{Arc, Mutex};
+  use tokio::net::TcpListener;
+
+  const ENHANCED: &str = include_str!("../tests/fixtures/webhook_enhanced.json");
+  const RAW: &str = include_str!("../tests/fixtures/webhook_raw.json");
+
+  #[derive(Clone, Default)]
+  struct Recorder {
+    events: Arc<Mutex<Vec<WebhookEvent>>>,
+  }
+
+  impl Recorder {
+    fn take(&self) -> color_eyre::Result<Vec<WebhookEvent>> {
+      Ok(std::mem::take(&mut *self.events.lock().map_err(|_| format_err!("poisoned"))?))
+    }
+  }
+
+  #[async_trait]
+  impl WebhookHandler for Recorder {
+    async fn handle(&self, event: WebhookEvent) -> Result<(), HandlerError> {
+      if let WebhookEvent::Raw(txns) = &event {
+        if txns.is_empty() {
+          return Err("empty".into());
+        }
+      }
+      self.events.lock().map_err(|_| "poisoned")?.push(event);
+      Ok(())
+    }
+  }
+
+  async fn start(server: WebhookServer<Recorder>) -> color_eyre::Result<String> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/hook", listener.local_addr()?);
+    tokio::spawn(server.path("/hook").serve(listener));
+    Ok(url)
+  }
+
+  async fn post(url: &str, auth: Option<&str>, body: &str) -> color_eyre::Result<StatusCode> {
+    let mut req = reqwest::Client::new().post(url).body(body.to_string());
+    if let Some(auth) = auth {
+      req = req.header("Authorization", auth);
+    }
+    Ok(req.send().await?.status())
+  }
+
+  #[tokio::test]
+  async fn receive_enhanced() -> color_eyre::Result<()> {
+    let recorder = Recorder::default();
+    let data = WebhookData { auth_header: Some(String::from("secret")), ..Default::default() };
+    let url = start(WebhookServer::from_webhook(&data, recorder.clone())).await?;
+
+    assert_eq!(post(&url, None, ENHANCED).await?, StatusCode::UNAUTHORIZED);
+    assert_eq!(post(&url, Some("wrong"), ENHANCED).await?, StatusCode::UNAUTHORIZED);
+    assert_eq!(post(&url, Some("secret"), "{}").await?, StatusCode::BAD_REQUEST);
+    assert_eq!(post(&url, Some("secret"), ENHANCED).await?, StatusCode::OK);
+
+    let events = recorder.take()?;
+    assert_eq!(events.len(), 1);
+    match &events[0] {
+      WebhookEvent::Enhanced(txns) => assert_eq!(txns[0].fee, 5000),
+      WebhookEvent::Raw(_) => return Err(format_err!("should be enhanced")),
+    }
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn receive_raw() -> color_eyre::Result<()> {
+    let recorder = Recorder::default();
+    let url = start(WebhookServer::new(recorder.clone(), WebhookType::RawDevnet)).await?;
+    assert_eq!(post(&url, None, RAW).await?, StatusCode::OK);
+    assert_eq!(post(&url, None, "[]").await?, StatusCode::INTERNAL_SERVER_ERROR);
+    let events = recorder.take()?;
+    assert!(matches!(&events[0], WebhookEvent::Raw(txns) if txns.len() == 1 && txns[0].meta.fee == 5000));
+    assert_eq!(post(&url, None, ENHANCED).await?, StatusCode::BAD_REQUEST);
+    Ok(())
+  }
+
+  #[test]
+  fn discord_has_no_payload() {
+    for webhook_type in [WebhookType::Discord, WebhookType::DiscordDevnet] {
+      let result = WebhookEvent::parse(&webhook_type, RAW.as_bytes());
+      assert!(matches!(result, Err(HeliusError::InvalidWebhook { .. })));
+    }
+  }
+}
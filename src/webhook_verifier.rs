@@ -0,0 +1,204 @@
+//! Checks of the webhook POSTs Helius sends, usable from any HTTP framework.
+//!
+//! Helius sends the [`WebhookData::auth_header`] of a webhook as the `Authorization` header of every delivery.
+//!
+//! ```rust
+//! use selene_helius_sdk::webhook_verifier::{VerifyError, WebhookVerifier};
+//! use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+//! use std::time::Duration;
+//!
+//! let verifier = WebhookVerifier::new("secret").replay_window(Duration::from_secs(300));
+//! let mut headers = HeaderMap::new();
+//! headers.insert(AUTHORIZATION, HeaderValue::from_static("wrong"));
+//! assert_eq!(verifier.verify(&headers, b"[]").err(), Some(VerifyError::InvalidAuthHeader));
+//! ```
+use crate::api::types::enhanced::EnhancedTransaction;
+use crate::api::webhook::WebhookData;
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::StatusCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Why a webhook delivery was rejected
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+  #[error("Missing Authorization header")]
+  MissingAuthHeader,
+
+  #[error("Invalid Authorization header")]
+  InvalidAuthHeader,
+
+  #[error("Invalid webhook payload: {message}")]
+  InvalidPayload { message: String },
+
+  #[error("Transaction {signature} at {timestamp} is outside of the replay window")]
+  Replayed { signature: String, timestamp: u64 },
+}
+
+impl VerifyError {
+  /// Status to respond with, Helius retries deliveries which are not answered with a success
+  pub const fn status(&self) -> StatusCode {
+    match self {
+      Self::MissingAuthHeader | Self::InvalidAuthHeader => StatusCode::UNAUTHORIZED,
+      Self::InvalidPayload { .. } | Self::Replayed { .. } => StatusCode::BAD_REQUEST,
+    }
+  }
+}
+
+/// Compare without short-circuiting on the first differing byte or on a different length.
+/// The time depends on the length of `expected` only
+pub(crate) fn constant_time_eq(actual: &[u8], expected: &[u8]) -> bool {
+  let diff = expected.iter().enumerate().fold(actual.len() ^ expected.len(), |acc, (i, y)| {
+    acc | usize::from(actual.get(i).copied().unwrap_or_default() ^ y)
+  });
+  diff == 0
+}
+
+/// Verifies the auth header and the freshness of enhanced webhook deliveries
+#[derive(Clone, Default)]
+pub struct WebhookVerifier {
+  auth_header: Option<String>,
+  replay_window: Option<Duration>,
+}
+
+impl std::fmt::Debug for WebhookVerifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("WebhookVerifier")
+      .field("auth_header", &self.auth_header.as_ref().map(|_| "***"))
+      .field("replay_window", &self.replay_window)
+      .finish()
+  }
+}
+
+impl WebhookVerifier {
+  /// Accept deliveries whose `Authorization` header is `auth_header`
+  pub fn new(auth_header: &str) -> Self {
+    Self { auth_header: Some(auth_header.to_string()), replay_window: None }
+  }
+
+  /// Verifier of a webhook created with [`crate::Helius::create_webhook`], any header is accepted without
+  /// [`WebhookData::auth_header`]
+  pub fn from_webhook<A>(data: &WebhookData<A>) -> Self {
+    Self { auth_header: data.auth_header.clone(), replay_window: None }
+  }
+
+  /// Reject transactions whose timestamp is more than `window` away from now, e.g. replayed deliveries
+  #[must_use]
+  pub const fn replay_window(mut self, window: Duration) -> Self {
+    self.replay_window = Some(window);
+    self
+  }
+
+  /// Check the `Authorization` header only, e.g. for raw webhooks
+  ///
+  /// # Errors
+  ///
+  /// Will return [`VerifyError::MissingAuthHeader`] or [`VerifyError::InvalidAuthHeader`]
+  pub fn verify_auth(&self, headers: &HeaderMap) -> Result<(), VerifyError> {
+    let Some(expected) = &self.auth_header else {
+      return Ok(());
+    };
+    let actual = headers.get(AUTHORIZATION).map(HeaderValue::as_bytes).ok_or(VerifyError::MissingAuthHeader)?;
+    if constant_time_eq(actual, expected.as_bytes()) {
+      Ok(())
+    } else {
+      Err(VerifyError::InvalidAuthHeader)
+    }
+  }
+
+  /// Check the auth header, decode the body of an enhanced webhook and check the replay window
+  ///
+  /// # Errors
+  ///
+  /// Will return [`VerifyError`] when the delivery is rejected
+  pub fn verify(&self, headers: &HeaderMap, body: &[u8]) -> Result<Vec<EnhancedTransaction>, VerifyError> {
+    self.verify_at(headers, body, SystemTime::now())
+  }
+
+  /// [`WebhookVerifier::verify`] at the time `now`
+  ///
+  /// # Errors
+  ///
+  /// Will return [`VerifyError`] when the delivery is rejected
+  pub fn verify_at(
+    &self,
+    headers: &HeaderMap,
+    body: &[u8],
+    now: SystemTime,
+  ) -> Result<Vec<EnhancedTransaction>, VerifyError> {
+    self.verify_auth(headers)?;
+    let transactions: Vec<EnhancedTransaction> =
+      serde_json::from_slice(body).map_err(|err| VerifyError::InvalidPayload { message: err.to_string() })?;
+    if let Some(window) = self.replay_window {
+      let now = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+      if let Some(stale) = transactions.iter().find(|t| now.abs_diff(t.timestamp) > window.as_secs()) {
+        return Err(VerifyError::Replayed { signature: stale.signature.clone(), timestamp: stale.timestamp });
+      }
+    }
+    Ok(transactions)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::api::webhook::WebhookData;
+  use crate::webhook_verifier::{constant_time_eq, VerifyError, WebhookVerifier};
+  use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+  use reqwest::StatusCode;
+  use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+  const ENHANCED: &str = include_str!("../tests/fixtures/webhook_enhanced.json");
+  /// Timestamp of the transaction of [`ENHANCED`]
+  const TIMESTAMP: u64 = 1_673_445_241;
+
+  fn headers(auth: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(AUTHORIZATION, HeaderValue::from_static(auth));
+    headers
+  }
+
+  #[test]
+  fn compare_auth() {
+    assert!(constant_time_eq(b"secret", b"secret"));
+    assert!(!constant_time_eq(b"secret", b"secreT"));
+    assert!(!constant_time_eq(b"secret", b"secre"));
+    assert!(!constant_time_eq(b"secre", b"secret"));
+    assert!(!constant_time_eq(b"secret\0", b"secret"));
+    assert!(!constant_time_eq(b"", b"secret"));
+    assert!(constant_time_eq(b"", b""));
+  }
+
+  #[test]
+  fn verify_auth_header() -> color_eyre::Result<()> {
+    let verifier = WebhookVerifier::new("secret");
+    assert_eq!(verifier.verify(&headers("secret"), ENHANCED.as_bytes())?[0].fee, 5000);
+    assert_eq!(verifier.verify(&headers("wrong"), ENHANCED.as_bytes()).err(), Some(VerifyError::InvalidAuthHeader));
+    assert_eq!(verifier.verify(&HeaderMap::new(), ENHANCED.as_bytes()).err(), Some(VerifyError::MissingAuthHeader));
+    assert_eq!(VerifyError::MissingAuthHeader.status(), StatusCode::UNAUTHORIZED);
+    let invalid = verifier.verify(&headers("secret"), b"{}");
+    assert!(matches!(&invalid, Err(e @ VerifyError::InvalidPayload { .. }) if e.status() == StatusCode::BAD_REQUEST));
+    assert!(!format!("{verifier:?}").contains("secret"));
+
+    let open = WebhookVerifier::from_webhook(&WebhookData::default());
+    assert!(open.verify_auth(&HeaderMap::new()).is_ok());
+    Ok(())
+  }
+
+  #[test]
+  // `Duration::from_mins` needs a newer compiler than the crate supports
+  #[allow(clippy::duration_suboptimal_units)]
+  fn verify_replay_window() -> color_eyre::Result<()> {
+    let verifier = WebhookVerifier::new("secret").replay_window(Duration::from_secs(300));
+    let sent = UNIX_EPOCH + Duration::from_secs(TIMESTAMP);
+    let body = ENHANCED.as_bytes();
+    assert_eq!(verifier.verify_at(&headers("secret"), body, sent + Duration::from_secs(299))?.len(), 1);
+    assert!(verifier.verify_at(&headers("secret"), body, sent - Duration::from_secs(60)).is_ok());
+    match verifier.verify_at(&headers("secret"), body, sent + Duration::from_secs(301)) {
+      Err(VerifyError::Replayed { timestamp, .. }) => assert_eq!(timestamp, TIMESTAMP),
+      other => return Err(color_eyre::eyre::format_err!("should be replayed {other:?}")),
+    }
+    assert!(matches!(verifier.verify(&headers("secret"), body), Err(VerifyError::Replayed { .. })));
+    assert!(verifier.verify_at(&headers("secret"), b"[]", SystemTime::now()).is_ok());
+    Ok(())
+  }
+}